@@ -0,0 +1,99 @@
+use std::sync::RwLock;
+use std::time::Duration;
+
+use tauri::{AppHandle, Manager};
+
+use crate::config::Config;
+use crate::events::{ClipboardComicFoundEvent, ClipboardComicFoundEventPayload};
+use crate::extensions::IgnoreRwLockPoison;
+
+/// 轮询剪贴板的间隔，足够短以保证及时提醒，又不至于造成明显的系统调用开销
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// 后台轮询系统剪贴板，`Config.clipboard_watcher_enabled`开启时，
+/// 一旦发现剪贴板内容是哔咔漫画id或链接就发出[`ClipboardComicFoundEvent`]，交由前端弹窗询问是否下载；
+/// 关闭状态下完全不读取剪贴板
+///
+/// 不依赖任何剪贴板专用crate，而是像[`crate::download_manager::execute_power_action`]一样
+/// 按平台调用系统自带命令读取剪贴板文本内容
+pub async fn run_clipboard_watcher(app: AppHandle) {
+    let mut last_text = String::new();
+    loop {
+        tokio::time::sleep(POLL_INTERVAL).await;
+
+        let enabled = app
+            .state::<RwLock<Config>>()
+            .read_or_panic()
+            .clipboard_watcher_enabled;
+        if !enabled {
+            // 关闭期间不保留上次看到的内容，重新开启后即使剪贴板内容没变化也能再次提醒
+            last_text.clear();
+            continue;
+        }
+
+        let Some(text) = read_clipboard_text() else {
+            continue;
+        };
+        if text == last_text {
+            continue;
+        }
+        last_text = text.clone();
+
+        if let Some(comic_id) = extract_comic_id(&text) {
+            let payload = ClipboardComicFoundEventPayload { comic_id };
+            crate::events::emit_event(&app, ClipboardComicFoundEvent(payload));
+        }
+    }
+}
+
+/// 从剪贴板文本中提取哔咔漫画id：可以是裸的24位十六进制ObjectId，也可以是包含该id的链接
+fn extract_comic_id(text: &str) -> Option<String> {
+    let trimmed = text.trim();
+    if is_comic_id(trimmed) {
+        return Some(trimmed.to_string());
+    }
+    trimmed
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .find(|segment| is_comic_id(segment))
+        .map(str::to_string)
+}
+
+/// 哔咔漫画id是MongoDB ObjectId，固定为24位十六进制字符
+fn is_comic_id(s: &str) -> bool {
+    s.len() == 24 && s.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+#[cfg(target_os = "macos")]
+fn read_clipboard_text() -> Option<String> {
+    let output = std::process::Command::new("pbpaste").output().ok()?;
+    String::from_utf8(output.stdout).ok()
+}
+
+#[cfg(target_os = "windows")]
+fn read_clipboard_text() -> Option<String> {
+    let output = std::process::Command::new("powershell.exe")
+        .args(["-NoProfile", "-Command", "Get-Clipboard"])
+        .output()
+        .ok()?;
+    String::from_utf8(output.stdout).ok()
+}
+
+#[cfg(target_os = "linux")]
+fn read_clipboard_text() -> Option<String> {
+    // Wayland优先尝试wl-paste，失败(如X11环境下命令不存在)则回退到xclip
+    if let Ok(output) = std::process::Command::new("wl-paste").output() {
+        if output.status.success() {
+            return String::from_utf8(output.stdout).ok();
+        }
+    }
+    let output = std::process::Command::new("xclip")
+        .args(["-selection", "clipboard", "-o"])
+        .output()
+        .ok()?;
+    String::from_utf8(output.stdout).ok()
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+fn read_clipboard_text() -> Option<String> {
+    None
+}
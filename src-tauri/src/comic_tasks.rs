@@ -0,0 +1,26 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use crate::extensions::IgnoreLockPoison;
+
+/// 把[`JobRegistry`](crate::jobs::JobRegistry)按`ep_id`管理的章节下载任务按`comic_id`分组，
+/// 供[`crate::commands::pause_comic_download_task`]/[`crate::commands::cancel_comic_download_task`]
+/// 等command把同一漫画的所有章节作为一个整体暂停/恢复/取消，而不必让前端自己遍历章节列表逐个调用
+#[derive(Default)]
+pub struct ComicTaskRegistry(Mutex<HashMap<String, Vec<String>>>);
+impl ComicTaskRegistry {
+    /// 记录`comic_id`本次任务组包含的`ep_id`列表，若已存在旧分组则直接覆盖
+    pub fn register(&self, comic_id: &str, ep_ids: Vec<String>) {
+        self.0.lock_or_panic().insert(comic_id.to_string(), ep_ids);
+    }
+
+    /// 查询`comic_id`当前任务组包含的`ep_id`列表，不存在则返回`None`
+    pub fn ep_ids(&self, comic_id: &str) -> Option<Vec<String>> {
+        self.0.lock_or_panic().get(comic_id).cloned()
+    }
+
+    /// 移除并返回`comic_id`对应的任务组
+    pub fn remove(&self, comic_id: &str) -> Option<Vec<String>> {
+        self.0.lock_or_panic().remove(comic_id)
+    }
+}
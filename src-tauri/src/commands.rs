@@ -1,22 +1,82 @@
 #![allow(clippy::used_underscore_binding)]
 use std::path::PathBuf;
 use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, SystemTime};
 
-use anyhow::anyhow;
+use anyhow::{anyhow, Context};
+use chrono::Local;
 use path_slash::PathBufExt;
-use tauri::{AppHandle, State};
+use tauri::{AppHandle, Manager, State};
 use tokio::task::JoinSet;
 
+use crate::comic_tasks::ComicTaskRegistry;
 use crate::config::Config;
+use crate::content_index::ContentIndex;
 use crate::download_manager::DownloadManager;
+use crate::download_quota::DownloadQuotaStore;
+use crate::enums_meta::{self, EnumOption};
 use crate::errors::CommandResult;
-use crate::extensions::IgnoreRwLockPoison;
-use crate::pica_client::PicaClient;
+use crate::events::{
+    ArchiveVolumeCreatedEvent, ArchiveVolumeCreatedEventPayload, ExportFileSkippedEvent,
+    ExportFileSkippedEventPayload, ExportLongStripProgressEvent,
+    ExportLongStripProgressEventPayload, ExportZipProgressEvent, ExportZipProgressEventPayload,
+    HealthCheckProgressEvent, HealthCheckProgressEventPayload, LibraryDirSwitchedEvent,
+    LibraryDirSwitchedEventPayload, NewTagComicFoundEvent, NewTagComicFoundEventPayload,
+    TranscodeProgressEvent, TranscodeProgressEventPayload,
+};
+use crate::export;
+use crate::extensions::{AnyhowErrorToStringChain, IgnoreLockPoison, IgnoreRwLockPoison};
+use crate::favorites_download_queue::FavoritesDownloadQueueStore;
+use crate::library_index::{LibraryIndex, LocalComicMeta};
+use crate::log::{FrontendLogState, LogLevel};
+use crate::opds;
+use crate::opds::OpdsHandle;
+use crate::pending_downloads::PendingDownloadsStore;
+use crate::pica_api::PicaApi;
+use crate::jobs::JobRegistry;
+use crate::share;
+use crate::share::ShareState;
+use crate::tag_subscriptions::{TagSubscription, TagSubscriptionStore};
+use crate::thumbnails;
+use crate::transcode;
+use crate::transcode::TranscodeFormat;
 use crate::responses::{
-    ComicInFavoriteRespData, ComicInSearchRespData, EpisodeImageRespData, Pagination,
-    UserProfileDetailRespData,
+    AnnouncementRespData, CategoryRespData, ComicInFavoriteRespData, ComicInSearchRespData,
+    CommentRespData, EpisodeImageRespData, EpisodeRespData, Pagination, UserProfileDetailRespData,
+};
+use crate::types::{
+    ApiChannelLatency, AppInfo, BatchFailure, BatchSummary, CircuitBreakerStatus, Comic,
+    ComicComparisonEntry,
+    ComicComparisonReport, ComicDiskUsage, ComicDownloadProgress, ComicManifestEntry,
+    CoverageReport, DebugDownloadImageResult, DisallowedDownloadPolicy, DownloadPreview,
+    DownloadQuotaStatus,
+    Episode, EpisodeSelection, FavoriteSyncDirection, FavoriteSyncReport, HealthCheckItem,
+    ImageFormatReport, LibraryManifest, MissingEpisodes, PinyinGroup, SearchResultGroup, Sort,
+    SpeedSample,
 };
-use crate::types::{Comic, Episode, Sort};
+use crate::utils::{dir_size, group_by_pinyin, group_by_similar_title};
+use crate::reading_progress::{EpisodeProgress, ReadingProgressStore};
+use crate::wishlist::{Wishlist, WishlistItem};
+
+/// 访客模式下拒绝执行会修改磁盘或发起批量请求的command，只保留浏览类command；
+/// 由各command在真正执行修改前自行调用，`save_config`不受此限制，否则访客模式一旦开启将无法被关闭
+fn ensure_not_guest_mode(app: &AppHandle) -> CommandResult<()> {
+    let config = app.state::<RwLock<Config>>().read_or_panic();
+    if config.guest_mode {
+        return Err(anyhow!(crate::i18n::t(config.language, "guest_mode_disabled", &[])).into());
+    }
+    Ok(())
+}
+
+/// 离线模式下拒绝执行所有需要联网的command（登录、搜索、拉取漫画详情/图片等），
+/// 由各网络入口command在真正发起请求前自行调用；浏览本地已下载内容的command不受此限制
+pub fn ensure_not_offline_mode(app: &AppHandle) -> CommandResult<()> {
+    let config = app.state::<RwLock<Config>>().read_or_panic();
+    if config.offline_mode {
+        return Err(anyhow!(crate::i18n::t(config.language, "offline_mode_disabled", &[])).into());
+    }
+    Ok(())
+}
 
 #[tauri::command]
 #[specta::specta]
@@ -42,16 +102,25 @@ pub fn save_config(
     let mut config_state = config_state.write_or_panic();
     *config_state = config;
     config_state.save(&app)?;
+    crate::config::emit_if_download_dir_unwritable(
+        &app,
+        &config_state.download_dir,
+        config_state.language,
+    );
+    drop(config_state);
+    app.state::<crate::config::ConfigChangeNotifier>().notify();
     Ok(())
 }
 
 #[tauri::command(async)]
 #[specta::specta]
 pub async fn login(
-    pica_client: State<'_, PicaClient>,
+    app: AppHandle,
+    pica_client: State<'_, Arc<dyn PicaApi>>,
     email: String,
     password: String,
 ) -> CommandResult<String> {
+    ensure_not_offline_mode(&app)?;
     let token = pica_client.login(&email, &password).await?;
     Ok(token)
 }
@@ -59,34 +128,135 @@ pub async fn login(
 #[tauri::command(async)]
 #[specta::specta]
 pub async fn get_user_profile(
-    pica_client: State<'_, PicaClient>,
+    app: AppHandle,
+    pica_client: State<'_, Arc<dyn PicaApi>>,
 ) -> CommandResult<UserProfileDetailRespData> {
+    ensure_not_offline_mode(&app)?;
     let user_profile = pica_client.get_user_profile().await?;
     Ok(user_profile)
 }
 
+/// 获取官方公告/声明(例如接口维护信息)，供前端展示，帮助用户理解部分操作失败的原因
+#[tauri::command(async)]
+#[specta::specta]
+pub async fn get_announcements(
+    app: AppHandle,
+    pica_client: State<'_, Arc<dyn PicaApi>>,
+    page: i64,
+) -> CommandResult<Pagination<AnnouncementRespData>> {
+    ensure_not_offline_mode(&app)?;
+    let announcement_pagination = pica_client.get_announcements(page).await?;
+    Ok(announcement_pagination)
+}
+
+/// 获取官方分类列表，供搜索/分类筛选使用，取代前端硬编码的分类，保证筛选项与官方同步
+#[tauri::command(async)]
+#[specta::specta]
+pub async fn get_categories(
+    app: AppHandle,
+    pica_client: State<'_, Arc<dyn PicaApi>>,
+) -> CommandResult<Vec<CategoryRespData>> {
+    ensure_not_offline_mode(&app)?;
+    let categories = pica_client.get_categories().await?;
+    Ok(categories)
+}
+
+/// 获取漫画`comic_id`的评论分页，方便在下载前于工具内查看评论判断质量
+#[tauri::command(async)]
+#[specta::specta]
+pub async fn get_comments(
+    app: AppHandle,
+    pica_client: State<'_, Arc<dyn PicaApi>>,
+    comic_id: String,
+    page: i64,
+) -> CommandResult<Pagination<CommentRespData>> {
+    ensure_not_offline_mode(&app)?;
+    let comment_pagination = pica_client.get_comments(&comic_id, page).await?;
+    Ok(comment_pagination)
+}
+
+/// 点赞`comic_id`，返回操作后的点赞状态(`true`表示已点赞)，方便下载后顺手给作者点赞
+#[tauri::command(async)]
+#[specta::specta]
+pub async fn like_comic(
+    app: AppHandle,
+    pica_client: State<'_, Arc<dyn PicaApi>>,
+    comic_id: String,
+) -> CommandResult<bool> {
+    ensure_not_offline_mode(&app)?;
+    ensure_not_guest_mode(&app)?;
+    let is_liked = pica_client.like_comic(&comic_id).await?;
+    Ok(is_liked)
+}
+
+/// 依次测试`app-channel`各条分流线路的延迟，供前端展示后由用户自行在设置中切换`api_channel`；
+/// 不修改当前配置
+#[tauri::command(async)]
+#[specta::specta]
+pub async fn test_channels(
+    app: AppHandle,
+    pica_client: State<'_, Arc<dyn PicaApi>>,
+) -> CommandResult<Vec<ApiChannelLatency>> {
+    ensure_not_offline_mode(&app)?;
+    Ok(pica_client.test_channels().await)
+}
+
+/// 按当前`Config.language`返回排序方式、下载格式等枚举的下拉框选项(取值+翻译好的展示文案)，
+/// 前端渲染下拉框时直接使用，不必再手写维护一份同样的常量，见[`crate::enums_meta::enums_meta`]
+#[tauri::command(async)]
+#[specta::specta]
+#[allow(clippy::needless_pass_by_value)]
+pub fn get_enums_meta(
+    config: State<RwLock<Config>>,
+) -> CommandResult<std::collections::HashMap<String, Vec<EnumOption>>> {
+    let language = config.read_or_panic().language;
+    Ok(enums_meta::enums_meta(language))
+}
+
 #[tauri::command(async)]
 #[specta::specta]
 pub async fn search_comic(
-    pica_client: State<'_, PicaClient>,
+    app: AppHandle,
+    pica_client: State<'_, Arc<dyn PicaApi>>,
     keyword: String,
     sort: Sort,
     page: i32,
     categories: Vec<String>,
 ) -> CommandResult<Pagination<ComicInSearchRespData>> {
+    ensure_not_offline_mode(&app)?;
     let comic_in_search_pagination = pica_client
         .search_comic(&keyword, sort, page, categories)
         .await?;
     Ok(comic_in_search_pagination)
 }
 
+/// 在`search_comic`的基础上，对当前页结果按归一化标题分组，标记出疑似同一作品的多个重复上传版本，
+/// 用户可以据此选择页数最多/最新的版本下载，而不是被迫在搜索结果里重复比对
+#[tauri::command(async)]
+#[specta::specta]
+pub async fn search_comic_grouped(
+    app: AppHandle,
+    pica_client: State<'_, Arc<dyn PicaApi>>,
+    keyword: String,
+    sort: Sort,
+    page: i32,
+    categories: Vec<String>,
+) -> CommandResult<Vec<SearchResultGroup>> {
+    ensure_not_offline_mode(&app)?;
+    let comic_in_search_pagination = pica_client
+        .search_comic(&keyword, sort, page, categories)
+        .await?;
+    Ok(group_by_similar_title(comic_in_search_pagination.docs))
+}
+
 #[tauri::command(async)]
 #[specta::specta]
 pub async fn get_comic(
     app: AppHandle,
-    pica_client: State<'_, PicaClient>,
+    pica_client: State<'_, Arc<dyn PicaApi>>,
     comic_id: String,
 ) -> CommandResult<Comic> {
+    ensure_not_offline_mode(&app)?;
     let pica_client = pica_client.inner().clone();
     // 获取漫画详情和章节的第一页
     let comic_task = pica_client.get_comic(&comic_id);
@@ -117,55 +287,732 @@ pub async fn get_comic(
         episodes.sort_by_key(|ep| ep.order);
         std::mem::take(&mut *episodes)
     };
-    let comic = Comic::from(&app, comic, episodes);
+    // 有些漫画的eps接口会异常返回空章节列表，但漫画详情已显示存在章节，这里尽力通过探测图片接口恢复
+    let (episodes, episodes_recovered) = if episodes.is_empty() && comic.eps_count > 0 {
+        let recovered =
+            recover_episodes_by_probing(&pica_client, &comic_id, comic.eps_count, comic.updated_at)
+                .await;
+        let episodes_recovered = !recovered.is_empty();
+        (recovered, episodes_recovered)
+    } else {
+        (episodes, false)
+    };
+    let comic = Comic::from(&app, comic, episodes, episodes_recovered);
 
     Ok(comic)
 }
 
+/// 当`eps`接口异常返回空章节列表，但漫画详情显示`eps_count > 0`时，尝试按`order`从1开始
+/// 递增探测图片接口(`get_episode_image`)，尽力恢复出一份章节列表；恢复出的章节标题只能是占位的
+/// "第N话"，真实标题已随官方接口异常而不可得，见[`crate::types::Comic::episodes_recovered`]
+async fn recover_episodes_by_probing(
+    pica_client: &Arc<dyn PicaApi>,
+    comic_id: &str,
+    eps_count: i64,
+    updated_at: chrono::DateTime<chrono::Utc>,
+) -> Vec<EpisodeRespData> {
+    let recovered = Arc::new(Mutex::new(vec![]));
+    let mut join_set = JoinSet::new();
+    for order in 1..=eps_count {
+        let pica_client = pica_client.clone();
+        let comic_id = comic_id.to_string();
+        let recovered = recovered.clone();
+        join_set.spawn(async move {
+            let Ok(image_page) = pica_client.get_episode_image(&comic_id, order, 1).await else {
+                return;
+            };
+            if image_page.docs.is_empty() {
+                return;
+            }
+            recovered.lock().unwrap().push(EpisodeRespData {
+                id: format!("recovered-{order}"),
+                title: format!("第{order}话"),
+                order,
+                updated_at,
+                is_locked: false,
+            });
+        });
+    }
+    join_set.join_all().await;
+    let mut recovered = recovered.lock().unwrap();
+    recovered.sort_by_key(|ep| ep.order);
+    std::mem::take(&mut *recovered)
+}
+
+/// 并发拉取多个漫画的详情并汇总成对比表，帮助用户从哔咔上同一作品的多个重复上传版本中
+/// 挑选页数最多/更新最新的版本；只获取漫画详情，不拉取章节列表，避免不必要的请求
+#[tauri::command(async)]
+#[specta::specta]
+pub async fn compare_comics(
+    app: AppHandle,
+    pica_client: State<'_, Arc<dyn PicaApi>>,
+    comic_ids: Vec<String>,
+) -> CommandResult<ComicComparisonReport> {
+    ensure_not_offline_mode(&app)?;
+    let pica_client = pica_client.inner().clone();
+    let mut join_set = JoinSet::new();
+    for comic_id in comic_ids {
+        let pica_client = pica_client.clone();
+        join_set.spawn(async move {
+            let result = pica_client.get_comic(&comic_id).await;
+            (comic_id, result)
+        });
+    }
+
+    let mut entries = vec![];
+    let mut failed_comic_ids = vec![];
+    for (comic_id, result) in join_set.join_all().await {
+        match result {
+            Ok(comic) => entries.push(ComicComparisonEntry {
+                comic_id,
+                title: comic.title,
+                pages_count: comic.pages_count,
+                eps_count: comic.eps_count,
+                updated_at: comic.updated_at,
+                tags: comic.tags,
+                unique_tags: vec![],
+            }),
+            Err(_) => failed_comic_ids.push(comic_id),
+        }
+    }
+
+    for i in 0..entries.len() {
+        let others_tags: std::collections::HashSet<&String> = entries
+            .iter()
+            .enumerate()
+            .filter(|(j, _)| *j != i)
+            .flat_map(|(_, entry)| entry.tags.iter())
+            .collect();
+        entries[i].unique_tags = entries[i]
+            .tags
+            .iter()
+            .filter(|tag| !others_tags.contains(tag))
+            .cloned()
+            .collect();
+    }
+
+    Ok(ComicComparisonReport {
+        entries,
+        failed_comic_ids,
+    })
+}
+
 #[tauri::command(async)]
 #[specta::specta]
 pub async fn get_episode_image(
-    pica_client: State<'_, PicaClient>,
+    app: AppHandle,
+    pica_client: State<'_, Arc<dyn PicaApi>>,
     comic_id: String,
     episode_order: i64,
     page: i64,
 ) -> CommandResult<Pagination<EpisodeImageRespData>> {
+    ensure_not_offline_mode(&app)?;
     let episode_image_pagination = pica_client
         .get_episode_image(&comic_id, episode_order, page)
         .await?;
     Ok(episode_image_pagination)
 }
 
+/// 用本地图片文件手动替换已下载章节中的某一页，用于官方源图片本身损坏的场景；
+/// 替换后按该页原有的文件格式重新编码保存，不改变章节目录下其余图片的格式。
+/// 替换后会重新计算该页的内容哈希并登记到[`ContentIndex`]，避免跨章节去重命中过时的哈希
+#[tauri::command(async)]
+#[specta::specta]
+pub async fn replace_chapter_page(
+    app: AppHandle,
+    pica_client: State<'_, Arc<dyn PicaApi>>,
+    comic_id: String,
+    chapter_id: String,
+    page_index: i64,
+    file_path: PathBuf,
+) -> CommandResult<()> {
+    ensure_not_guest_mode(&app)?;
+    ensure_not_offline_mode(&app)?;
+    let pica_client = pica_client.inner().clone();
+    let comic = pica_client.get_comic(&comic_id).await?;
+    let ep_title = find_episode_title(&pica_client, &comic_id, &chapter_id).await?;
+
+    let ep_title = crate::utils::filename_filter(&app, &ep_title);
+    let download_with_author = app
+        .state::<RwLock<Config>>()
+        .read_or_panic()
+        .download_with_author;
+    let comic_title = crate::utils::filename_filter(&app, &comic.title);
+    let author = crate::utils::filename_filter(&app, &comic.author);
+    let comic_title = if download_with_author {
+        format!("[{author}] {comic_title}")
+    } else {
+        comic_title
+    };
+    let download_dir = app
+        .state::<RwLock<Config>>()
+        .read_or_panic()
+        .download_dir
+        .clone();
+    let ep_dir = download_dir.join(&comic_title).join(&ep_title);
+    let stem = ep_dir.join(format!("{:03}", page_index + 1));
+    let existing_path = crate::download_manager::find_valid_existing_image(&stem)
+        .ok_or_else(|| anyhow!("未找到`{ep_dir:?}`第`{page_index}`页已下载的图片"))?;
+    let target_format = existing_path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .and_then(|ext| match ext {
+            "jpg" | "jpeg" => Some(image::ImageFormat::Jpeg),
+            "png" => Some(image::ImageFormat::Png),
+            "webp" => Some(image::ImageFormat::WebP),
+            _ => None,
+        })
+        .ok_or_else(|| anyhow!("无法识别`{existing_path:?}`的图片格式"))?;
+
+    let img = image::open(&file_path).map_err(|e| anyhow!("读取`{file_path:?}`失败: {e}"))?;
+    // 先写入同目录下的临时文件再rename覆盖`existing_path`，而不是用`save_with_format`原地截断写入：
+    // `existing_path`可能是`Config.cross_episode_dedup_enabled`产生的硬链接，原地截断写入会
+    // 连带污染其它章节/漫画里共享同一物理文件的图片；rename覆盖则只是让这个路径指向新内容，
+    // 不影响硬链接的另一端
+    let tmp_path = existing_path.with_extension("tmp");
+    img.save_with_format(&tmp_path, target_format)
+        .map_err(|e| anyhow!("保存`{tmp_path:?}`失败: {e}"))?;
+    std::fs::rename(&tmp_path, &existing_path)
+        .map_err(|e| anyhow!("将`{tmp_path:?}`重命名为`{existing_path:?}`失败: {e}"))?;
+    // 替换后的内容已不同于登记时的内容，重新计算哈希登记为新的规范路径，
+    // 避免后续下载误命中`ContentIndex`里这个已经过时的哈希
+    let new_data = std::fs::read(&existing_path)
+        .map_err(|e| anyhow!("读取`{existing_path:?}`失败: {e}"))?;
+    let mut content_index = app.state::<RwLock<ContentIndex>>().write_or_panic();
+    content_index.record(&new_data, existing_path.clone());
+    let _ = content_index.save(&app);
+
+    Ok(())
+}
+
+/// 在`comic_id`的所有章节分页中查找`ep_id`对应的章节标题，用于只知道章节id、
+/// 不知道其所在目录名时定位本地文件(目录名由章节标题决定，见[`crate::types::Episode::dir_path`])
+async fn find_episode_title(
+    pica_client: &Arc<dyn PicaApi>,
+    comic_id: &str,
+    ep_id: &str,
+) -> anyhow::Result<String> {
+    let mut page = 1;
+    loop {
+        let episode_page = pica_client.get_episode(comic_id, page).await?;
+        if let Some(ep) = episode_page.docs.iter().find(|ep| ep.id == ep_id) {
+            return Ok(ep.title.clone());
+        }
+        if page >= episode_page.pages {
+            return Err(anyhow!("未找到漫画`{comic_id}`的章节`{ep_id}`"));
+        }
+        page += 1;
+    }
+}
+
 #[tauri::command(async)]
 #[specta::specta]
 pub async fn download_episodes(
+    app: AppHandle,
     download_manager: State<'_, DownloadManager>,
     episodes: Vec<Episode>,
-) -> CommandResult<()> {
+) -> CommandResult<BatchSummary> {
+    ensure_not_guest_mode(&app)?;
+    let mut summary = BatchSummary::default();
+    let (locked, episodes): (Vec<Episode>, Vec<Episode>) =
+        episodes.into_iter().partition(|ep| ep.is_locked);
+    if !locked.is_empty() {
+        summary.skipped += locked.len() as u32;
+        summary.warning = Some(format!(
+            "已跳过{}个锁定/付费章节: {}",
+            locked.len(),
+            locked
+                .iter()
+                .map(|ep| ep.ep_title.as_str())
+                .collect::<Vec<_>>()
+                .join("、")
+        ));
+    }
     for ep in episodes {
-        download_manager.submit_episode(ep).await?;
+        match download_manager.submit_episode(ep.clone()).await {
+            Ok(()) => summary.succeeded += 1,
+            Err(err) => summary.failures.push(BatchFailure {
+                item: ep.ep_title,
+                reason: err.to_string_chain(),
+            }),
+        }
     }
-    Ok(())
+    Ok(summary)
+}
+
+/// 以漫画为单位创建一个下载任务组：把`episodes`提交给[`DownloadManager`]批量下载，并在
+/// [`ComicTaskRegistry`]中记下这批`ep_id`，供[`pause_comic_download_task`]/
+/// [`resume_comic_download_task`]/[`cancel_comic_download_task`]按漫画整体操作，
+/// 不必让前端自己遍历章节列表逐个调用；漫画级别的聚合进度复用已有的`ComicDownloadProgressEvent`
+/// (见[`DownloadManager::submit_episode`])，这里不需要重复发送
+#[tauri::command(async)]
+#[specta::specta]
+pub async fn create_comic_download_task(
+    app: AppHandle,
+    download_manager: State<'_, DownloadManager>,
+    comic_tasks: State<'_, ComicTaskRegistry>,
+    comic_id: String,
+    episodes: Vec<Episode>,
+) -> CommandResult<BatchSummary> {
+    comic_tasks.register(&comic_id, episodes.iter().map(|ep| ep.ep_id.clone()).collect());
+    download_episodes(app, download_manager, episodes).await
+}
+
+/// 暂停`comic_id`对应任务组中所有尚未完成的章节下载，返回实际暂停的章节数；
+/// 复用[`JobRegistry`]已有的取消能力逐个取消该组的`ep_id`，已派发给线程池的图片仍会下载完成，
+/// 与单章节取消(见[`cancel_job`])行为一致
+#[tauri::command(async)]
+#[specta::specta]
+#[allow(clippy::needless_pass_by_value)]
+pub fn pause_comic_download_task(
+    jobs: State<JobRegistry>,
+    comic_tasks: State<ComicTaskRegistry>,
+    comic_id: String,
+) -> CommandResult<u32> {
+    let Some(ep_ids) = comic_tasks.ep_ids(&comic_id) else {
+        return Err(anyhow!("未找到漫画`{comic_id}`对应的下载任务").into());
+    };
+    let paused_count = ep_ids.iter().filter(|ep_id| jobs.cancel(ep_id)).count() as u32;
+    Ok(paused_count)
+}
+
+/// 恢复`comic_id`的下载：重新拉取该漫画当前的章节列表，把其中尚未下载完成的章节重新提交下载，
+/// 并刷新[`ComicTaskRegistry`]中记录的任务组，供后续再次暂停/取消
+#[tauri::command(async)]
+#[specta::specta]
+pub async fn resume_comic_download_task(
+    app: AppHandle,
+    pica_client: State<'_, Arc<dyn PicaApi>>,
+    download_manager: State<'_, DownloadManager>,
+    comic_tasks: State<'_, ComicTaskRegistry>,
+    comic_id: String,
+) -> CommandResult<BatchSummary> {
+    let comic = get_comic(app.clone(), pica_client, comic_id.clone()).await?;
+    let to_resume: Vec<Episode> = comic.episodes.into_iter().filter(|ep| !ep.is_downloaded).collect();
+    comic_tasks.register(&comic_id, to_resume.iter().map(|ep| ep.ep_id.clone()).collect());
+    download_episodes(app, download_manager, to_resume).await
+}
+
+/// 彻底取消`comic_id`对应的任务组，返回实际取消的章节数；取消后该漫画从[`ComicTaskRegistry`]中移除，
+/// 需要重新调用[`create_comic_download_task`]或[`resume_comic_download_task`]才能再次下载
+#[tauri::command(async)]
+#[specta::specta]
+#[allow(clippy::needless_pass_by_value)]
+pub fn cancel_comic_download_task(
+    jobs: State<JobRegistry>,
+    comic_tasks: State<ComicTaskRegistry>,
+    comic_id: String,
+) -> CommandResult<u32> {
+    let Some(ep_ids) = comic_tasks.remove(&comic_id) else {
+        return Err(anyhow!("未找到漫画`{comic_id}`对应的下载任务").into());
+    };
+    let cancelled_count = ep_ids.iter().filter(|ep_id| jobs.cancel(ep_id)).count() as u32;
+    Ok(cancelled_count)
+}
+
+/// 确定本次下载任务实际要使用的保存目录：
+/// - 用户显式传入`explicit_target_dir`时直接使用，不做空间检查(用户主动选择，信任其判断)
+/// - 否则，若`Config.low_disk_space_threshold_mb`已设置，检查默认下载目录(`download_dir`)的剩余空间，
+///   不足时按`library_dirs`顺序寻找第一个空间足够的库分区并切换过去，同时发出[`LibraryDirSwitchedEvent`]通知前端
+/// - 默认目录空间足够、未设置阈值、或无法判断剩余空间(如[`crate::utils::available_space`]查询失败)时，
+///   都返回`None`，让调用方沿用原有的默认目录逻辑
+pub(crate) fn resolve_download_target_dir(
+    app: &AppHandle,
+    config: &State<RwLock<Config>>,
+    comic_title: &str,
+    explicit_target_dir: Option<PathBuf>,
+) -> Option<PathBuf> {
+    if explicit_target_dir.is_some() {
+        return explicit_target_dir;
+    }
+    let (threshold_bytes, library_dirs) = {
+        let config = config.read_or_panic();
+        let threshold_mb = config.low_disk_space_threshold_mb?;
+        (threshold_mb * 1024 * 1024, config.all_library_dirs())
+    };
+    let default_dir = library_dirs.first()?;
+    let has_enough_space = |dir: &std::path::Path| match crate::utils::available_space(dir) {
+        Some(space) => space >= threshold_bytes,
+        None => true, // 无法判断时视为空间足够，避免误判导致不必要的切换
+    };
+    if has_enough_space(&default_dir.dir) {
+        return None;
+    }
+    let switch_to = library_dirs.iter().skip(1).find(|d| has_enough_space(&d.dir))?;
+    crate::events::emit_event(
+        app,
+        LibraryDirSwitchedEvent(LibraryDirSwitchedEventPayload {
+            comic_title: comic_title.to_string(),
+            from_label: default_dir.label.clone(),
+            to_label: switch_to.label.clone(),
+            to_dir: switch_to.dir.to_string_lossy().to_string(),
+        }),
+    );
+    Some(switch_to.dir.clone())
 }
 
 #[tauri::command(async)]
 #[specta::specta]
 pub async fn download_comic(
     app: AppHandle,
-    pica_client: State<'_, PicaClient>,
+    pica_client: State<'_, Arc<dyn PicaApi>>,
+    download_manager: State<'_, DownloadManager>,
+    config: State<'_, RwLock<Config>>,
+    library_index: State<'_, RwLock<LibraryIndex>>,
+    comic_id: String,
+    /// 下载来源上下文，例如`search:关键词`、`rank:day`、`favorite`，写入本地元数据供日后按来源筛选
+    source: Option<String>,
+    /// 章节选择策略，`None`表示下载全部章节；用于"只下载前N话/最新N话"等场景，减少一次性任务量
+    episode_selection: Option<EpisodeSelection>,
+    /// 本次任务的保存目录，覆盖全局`download_dir`，用于把个别漫画下载到特定位置(如不同磁盘)
+    target_dir: Option<PathBuf>,
+) -> CommandResult<BatchSummary> {
+    ensure_not_guest_mode(&app)?;
+    ensure_not_offline_mode(&app)?;
+    let comic = get_comic(app.clone(), pica_client, comic_id).await?;
+    let target_dir = resolve_download_target_dir(&app, &config, &comic.title, target_dir);
+
+    {
+        let mut library_index = library_index.write_or_panic();
+        library_index.set_finished(&comic.title, comic.finished);
+        if source.is_some() {
+            library_index.set_source(&comic.title, source);
+        }
+        if target_dir.is_some() {
+            library_index.set_target_dir(&comic.title, target_dir.clone());
+        }
+        library_index.save(&app)?;
+    }
+
+    if !comic.allow_download {
+        match config.read_or_panic().disallowed_download_policy {
+            DisallowedDownloadPolicy::Skip => {
+                return Ok(BatchSummary {
+                    skipped: comic.episodes.len() as u32,
+                    warning: Some(format!(
+                        "漫画`{}`被官方标记为禁止下载，已跳过",
+                        comic.title
+                    )),
+                    ..Default::default()
+                });
+            }
+            DisallowedDownloadPolicy::Warn | DisallowedDownloadPolicy::Force => {}
+        }
+    }
+
+    let total_episode_count = comic.episodes.len();
+    let mut selected_episodes = match episode_selection {
+        Some(selection) => selection.apply(comic.episodes),
+        None => comic.episodes,
+    };
+    if target_dir.is_some() {
+        for ep in &mut selected_episodes {
+            ep.target_dir = target_dir.clone();
+        }
+    }
+    let mut ep_titles: Vec<String> = selected_episodes.iter().map(|ep| ep.ep_title.clone()).collect();
+    ep_titles.sort();
+    // 被选择策略排除在外的章节也计入`skipped`，使summary能反映出本次实际覆盖了多少章节
+    let excluded_by_selection = total_episode_count - selected_episodes.len();
+
+    // 已在任意一个库分区/版本目录中存在的章节，不重复创建下载任务
+    let (downloaded, to_download): (Vec<Episode>, Vec<Episode>) =
+        selected_episodes.into_iter().partition(|ep| ep.is_downloaded);
+
+    let mut summary = download_episodes(app.clone(), download_manager, to_download).await?;
+
+    if config.read_or_panic().export_info_file {
+        let download_with_author = config.read_or_panic().download_with_author;
+        let comic_title = if download_with_author {
+            format!("[{}] {}", comic.author, comic.title)
+        } else {
+            comic.title.clone()
+        };
+        let base_dir = target_dir
+            .clone()
+            .unwrap_or_else(|| config.read_or_panic().download_dir.clone());
+        if let Err(err) = write_comic_info_file(
+            &base_dir.join(&comic_title),
+            &comic.title,
+            &comic.author,
+            &comic.tags,
+            &comic.description,
+            &ep_titles,
+        ) {
+            eprintln!("warn: 生成`{comic_title}`的info.txt失败: {err}");
+        }
+    }
+    summary.skipped += (downloaded.len() + excluded_by_selection) as u32;
+    if !comic.allow_download
+        && config.read_or_panic().disallowed_download_policy == DisallowedDownloadPolicy::Warn
+    {
+        summary.warning = Some(format!(
+            "漫画`{}`被官方标记为禁止下载，尝试强制下载可能会失败",
+            comic.title
+        ));
+    }
+    Ok(summary)
+}
+
+/// 在`comic_dir`下生成一份纯文本的`info.txt`，包含标题/作者/标签/简介/章节清单/生成时间，
+/// 不依赖任何特定阅读器即可浏览，也便于被文件系统全文检索；`Config.export_info_file`控制是否调用
+fn write_comic_info_file(
+    comic_dir: &std::path::Path,
+    title: &str,
+    author: &str,
+    tags: &[String],
+    description: &str,
+    ep_titles: &[String],
+) -> anyhow::Result<()> {
+    let mut content = String::new();
+    content.push_str(&format!("标题: {title}\n"));
+    content.push_str(&format!("作者: {author}\n"));
+    content.push_str(&format!("标签: {}\n", tags.join(", ")));
+    content.push_str(&format!("简介: {description}\n"));
+    content.push_str(&format!("章节数: {}\n", ep_titles.len()));
+    content.push_str("章节清单:\n");
+    for ep_title in ep_titles {
+        content.push_str(&format!("  - {ep_title}\n"));
+    }
+    content.push_str(&format!(
+        "下载时间: {}\n",
+        Local::now().format("%Y-%m-%d %H:%M:%S")
+    ));
+
+    let info_path = comic_dir.join("info.txt");
+    std::fs::write(&info_path, content).map_err(|e| anyhow!("写入`{info_path:?}`失败: {e}"))?;
+    Ok(())
+}
+
+/// 获取指定漫画当前的聚合下载进度(总章节数、已完成章节数、整体百分比)
+///
+/// 如果该漫画当前没有正在进行的下载任务，返回`None`
+#[tauri::command(async)]
+#[specta::specta]
+pub async fn get_comic_download_progress(
     download_manager: State<'_, DownloadManager>,
     comic_id: String,
+) -> CommandResult<Option<ComicDownloadProgress>> {
+    Ok(download_manager.get_comic_download_progress(&comic_id))
+}
+
+/// 获取最近一段时间内的下载速度采样序列，供前端绘制实时速度曲线，刷新页面也不会丢失历史数据
+#[tauri::command(async)]
+#[specta::specta]
+pub async fn get_speed_history(
+    download_manager: State<'_, DownloadManager>,
+) -> CommandResult<Vec<SpeedSample>> {
+    Ok(download_manager.get_speed_history())
+}
+
+/// 获取下载失败熔断([`crate::download_manager::DownloadManager::record_download_failure`])的当前状态
+#[tauri::command(async)]
+#[specta::specta]
+#[allow(clippy::needless_pass_by_value)]
+pub fn get_circuit_breaker_status(
+    download_manager: State<DownloadManager>,
+) -> CommandResult<CircuitBreakerStatus> {
+    let (broken, reason) = download_manager.circuit_breaker_status();
+    Ok(CircuitBreakerStatus { broken, reason })
+}
+
+/// 手动恢复下载失败熔断，无需等待`Config.circuit_breaker_cooldown_secs`冷却超时
+#[tauri::command(async)]
+#[specta::specta]
+#[allow(clippy::needless_pass_by_value)]
+pub fn resume_download_circuit_breaker(
+    app: AppHandle,
+    download_manager: State<DownloadManager>,
 ) -> CommandResult<()> {
-    let comic = get_comic(app, pica_client, comic_id).await?;
-    // TODO: 检查漫画的所有章节是否已存在于下载目录
-    if comic.episodes.is_empty() {
-        // TODO: 错误提示里添加漫画名
-        return Err(anyhow!("该漫画的所有章节都已存在于下载目录，无需重复下载").into());
+    ensure_not_guest_mode(&app)?;
+    download_manager.resume_circuit_breaker();
+    Ok(())
+}
+
+/// 取消正在倒计时的`Config.auto_power_action`(睡眠/关机)
+#[tauri::command(async)]
+#[specta::specta]
+#[allow(clippy::needless_pass_by_value)]
+pub fn cancel_auto_power_action(download_manager: State<DownloadManager>) -> CommandResult<()> {
+    download_manager.cancel_auto_power_action();
+    Ok(())
+}
+
+/// 获取当日下载量配额(`Config.daily_image_quota`/`daily_episode_quota`)的使用情况
+#[tauri::command(async)]
+#[specta::specta]
+#[allow(clippy::needless_pass_by_value)]
+pub fn get_download_quota_status(
+    config: State<RwLock<Config>>,
+    download_quota: State<RwLock<DownloadQuotaStore>>,
+) -> CommandResult<DownloadQuotaStatus> {
+    let config = config.read_or_panic();
+    let mut download_quota = download_quota.write_or_panic();
+    Ok(DownloadQuotaStatus {
+        downloaded_image_count: download_quota.image_count(),
+        image_quota: config.daily_image_quota,
+        downloaded_episode_count: download_quota.episode_count(),
+        episode_quota: config.daily_episode_quota,
+    })
+}
+
+/// 导出当前尚未完成的下载任务队列，供前端写入JSON文件；配合[`export_library_manifest`]/[`diff_with_manifest`]
+/// 可以先同步已下载完成的章节，再用这份任务队列在另一台机器上补齐正在下载中的部分，完成整体搬家
+#[tauri::command(async)]
+#[specta::specta]
+#[allow(clippy::needless_pass_by_value)]
+pub fn export_pending_downloads(
+    pending_downloads: State<RwLock<PendingDownloadsStore>>,
+) -> CommandResult<Vec<Episode>> {
+    Ok(pending_downloads.read_or_panic().episodes())
+}
+
+/// 导入一份任务队列，为其中每个章节重新创建下载任务；已下载过的章节会被[`DownloadManager`]正常识别为续传/跳过
+#[tauri::command(async)]
+#[specta::specta]
+pub async fn import_pending_downloads(
+    app: AppHandle,
+    download_manager: State<'_, DownloadManager>,
+    episodes: Vec<Episode>,
+) -> CommandResult<BatchSummary> {
+    download_episodes(app, download_manager, episodes).await
+}
+
+/// 获取版本、平台、数据目录与库统计概览，用户提issue时可以一键复制这些环境信息
+#[tauri::command(async)]
+#[specta::specta]
+#[allow(clippy::needless_pass_by_value)]
+pub fn get_app_info(app: AppHandle, config: State<RwLock<Config>>) -> CommandResult<AppInfo> {
+    let app_data_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| anyhow!("获取app_data_dir失败: {e}"))?;
+    let download_dir = config.read_or_panic().download_dir.clone();
+
+    let mut comic_count = 0;
+    let mut total_bytes = 0;
+    for (comic_title, library_dir) in create_comic_title_to_dir_map(&config)? {
+        comic_count += 1;
+        total_bytes += dir_size(&library_dir.join(&comic_title)).unwrap_or(0);
+    }
+
+    Ok(AppInfo {
+        version: app.package_info().version.to_string(),
+        os: std::env::consts::OS.to_string(),
+        arch: std::env::consts::ARCH.to_string(),
+        app_data_dir,
+        download_dir,
+        comic_count,
+        total_bytes,
+        emit_failure_count: app.state::<crate::events::EmitFailureStats>().count(),
+    })
+}
+
+/// 打包最近日志、脱敏后的配置、最近一次解析失败dump与环境信息为一个zip文件，
+/// 返回zip文件路径，降低用户提issue时复现问题、收集排查信息的门槛
+#[tauri::command(async)]
+#[specta::specta]
+#[allow(clippy::needless_pass_by_value)]
+pub fn collect_debug_bundle(
+    app: AppHandle,
+    config: State<RwLock<Config>>,
+    log_state: State<FrontendLogState>,
+) -> CommandResult<PathBuf> {
+    let app_data_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| anyhow!("获取app_data_dir失败: {e}"))?;
+
+    let mut redacted_config = config.read_or_panic().clone();
+    redacted_config.token = "***REDACTED***".to_string();
+    let config_json = serde_json::to_string_pretty(&redacted_config)
+        .map_err(|e| anyhow!("序列化配置失败: {e}"))?;
+
+    let recent_logs = log_state.recent().join("\n");
+
+    let app_info = get_app_info(app.clone(), config.clone())?;
+    let app_info_json = serde_json::to_string_pretty(&app_info)
+        .map_err(|e| anyhow!("序列化环境信息失败: {e}"))?;
+
+    let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S");
+    let output_path = app_data_dir.join(format!("debug_bundle_{timestamp}.zip"));
+    write_debug_bundle(
+        &output_path,
+        &config_json,
+        &recent_logs,
+        &app_info_json,
+        app_data_dir.join(crate::pica_client::PARSE_FAILURE_DUMP_FILENAME),
+    )?;
+
+    Ok(output_path)
+}
+
+fn write_debug_bundle(
+    output_path: &std::path::Path,
+    config_json: &str,
+    recent_logs: &str,
+    app_info_json: &str,
+    parse_failure_path: PathBuf,
+) -> anyhow::Result<()> {
+    let file =
+        std::fs::File::create(output_path).map_err(|e| anyhow!("创建`{output_path:?}`失败: {e}"))?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options =
+        zip::write::SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+
+    let write_entry = |zip: &mut zip::ZipWriter<std::fs::File>, name: &str, data: &[u8]| -> anyhow::Result<()> {
+        zip.start_file(name, options)
+            .map_err(|e| anyhow!("写入`{output_path:?}`的`{name}`失败: {e}"))?;
+        std::io::Write::write_all(zip, data).map_err(|e| anyhow!("写入`{output_path:?}`的`{name}`失败: {e}"))?;
+        Ok(())
+    };
+
+    write_entry(&mut zip, "config.json", config_json.as_bytes())?;
+    write_entry(&mut zip, "recent_logs.txt", recent_logs.as_bytes())?;
+    write_entry(&mut zip, "app_info.json", app_info_json.as_bytes())?;
+    if let Ok(data) = std::fs::read(&parse_failure_path) {
+        write_entry(&mut zip, "last_parse_failure.json", &data)?;
     }
-    download_episodes(download_manager, comic.episodes).await?;
+
+    zip.finish()
+        .map_err(|e| anyhow!("完成`{output_path:?}`的写入失败: {e}"))?;
+
     Ok(())
 }
 
+/// 走与正式下载相同的`client`与重试逻辑，单独调试下载某个图片URL，用于排查下载失败问题；
+/// 原始字节原样保存到`save_path`，不做任何格式转换/EXIF矫正
+#[tauri::command(async)]
+#[specta::specta]
+pub async fn debug_download_image(
+    app: AppHandle,
+    download_manager: State<'_, DownloadManager>,
+    url: String,
+    save_path: PathBuf,
+) -> CommandResult<DebugDownloadImageResult> {
+    ensure_not_offline_mode(&app)?;
+    ensure_not_guest_mode(&app)?;
+    let result = download_manager.debug_download_image(&url, &save_path).await?;
+    Ok(result)
+}
+
+#[tauri::command(async)]
+#[specta::specta]
+pub async fn preview_download_comic(
+    app: AppHandle,
+    pica_client: State<'_, Arc<dyn PicaApi>>,
+    comic_id: String,
+) -> CommandResult<DownloadPreview> {
+    // dry-run: 复用get_comic获取将要下载的章节列表，但不提交给download_manager
+    let comic = get_comic(app, pica_client, comic_id).await?;
+    let episode_titles = comic.episodes.iter().map(|ep| ep.ep_title.clone()).collect();
+    Ok(DownloadPreview {
+        comic_title: comic.title,
+        episode_count: comic.episodes.len(),
+        episode_titles,
+    })
+}
+
 #[tauri::command(async)]
 #[specta::specta]
 pub fn show_path_in_file_manager(path: &str) -> CommandResult<()> {
@@ -177,13 +1024,2296 @@ pub fn show_path_in_file_manager(path: &str) -> CommandResult<()> {
     Ok(())
 }
 
+#[tauri::command(async)]
+#[specta::specta]
+pub fn open_with_default_app(app: AppHandle, path: &str) -> CommandResult<()> {
+    use tauri_plugin_shell::ShellExt;
+
+    let path = PathBuf::from_slash(path);
+    if !path.exists() {
+        return Err(anyhow!("路径`{path:?}`不存在").into());
+    }
+    app.shell()
+        .open(path.to_string_lossy().to_string(), None)
+        .map_err(|e| anyhow!("用默认应用打开`{path:?}`失败: {e}"))?;
+    Ok(())
+}
+
 #[tauri::command(async)]
 #[specta::specta]
 pub async fn get_favorite_comics(
-    pica_client: State<'_, PicaClient>,
+    app: AppHandle,
+    pica_client: State<'_, Arc<dyn PicaApi>>,
     sort: Sort,
     page: i64,
 ) -> CommandResult<Pagination<ComicInFavoriteRespData>> {
+    ensure_not_offline_mode(&app)?;
     let favorite_comics = pica_client.get_favorite_comics(sort, page).await?;
     Ok(favorite_comics)
 }
+
+/// 本地库与哔咔收藏夹的双向同步：`direction`为`ToFavorites`时把本地已下载但未收藏的漫画批量
+/// 收藏；为`MarkRemovedLocally`时反向把本地已下载、但收藏夹中已不存在的漫画标记本地标签
+/// `已取消收藏`，不调用收藏API也不删除本地文件。`dry_run`为`true`时只返回将执行的操作，不实际生效
+///
+/// 本地只按标题管理已下载漫画(不持久化`comic_id`)，`ToFavorites`需要先按标题精确搜索反查
+/// `comic_id`，只有唯一匹配时才会继续收藏，找不到或匹配到多本的标题归入`unresolved`
+#[tauri::command(async)]
+#[specta::specta]
+#[allow(clippy::needless_pass_by_value)]
+pub async fn sync_library_to_favorites(
+    app: AppHandle,
+    pica_client: State<'_, Arc<dyn PicaApi>>,
+    config: State<'_, RwLock<Config>>,
+    library_index: State<'_, RwLock<LibraryIndex>>,
+    direction: FavoriteSyncDirection,
+    dry_run: bool,
+) -> CommandResult<FavoriteSyncReport> {
+    ensure_not_offline_mode(&app)?;
+    if !dry_run {
+        ensure_not_guest_mode(&app)?;
+    }
+    let local_titles: std::collections::HashSet<String> =
+        create_comic_title_to_dir_map(&config)?.into_keys().collect();
+    let favorite_titles = fetch_all_favorite_titles(&app, &pica_client).await?;
+
+    let mut not_favorited: Vec<String> = local_titles
+        .into_iter()
+        .filter(|title| !favorite_titles.contains(title))
+        .collect();
+    not_favorited.sort();
+
+    let mut report = FavoriteSyncReport::default();
+    match direction {
+        FavoriteSyncDirection::ToFavorites => {
+            for comic_title in not_favorited {
+                let comic_id = match resolve_comic_id_by_exact_title(&app, &pica_client, &comic_title).await {
+                    Ok(Some(comic_id)) => comic_id,
+                    Ok(None) => {
+                        report.unresolved.push(comic_title);
+                        continue;
+                    }
+                    Err(err) => {
+                        report.failures.push(BatchFailure {
+                            item: comic_title,
+                            reason: err.to_string_chain(),
+                        });
+                        continue;
+                    }
+                };
+                if dry_run {
+                    report.affected.push(comic_title);
+                    continue;
+                }
+                match pica_client.toggle_favorite(&comic_id).await {
+                    Ok(_) => report.affected.push(comic_title),
+                    Err(err) => report.failures.push(BatchFailure {
+                        item: comic_title,
+                        reason: err.to_string_chain(),
+                    }),
+                }
+            }
+        }
+        FavoriteSyncDirection::MarkRemovedLocally => {
+            for comic_title in not_favorited {
+                if !dry_run {
+                    let mut library_index = library_index.write_or_panic();
+                    library_index.add_tag(&comic_title, "已取消收藏");
+                    if let Err(err) = library_index.save(&app) {
+                        report.failures.push(BatchFailure {
+                            item: comic_title,
+                            reason: err.to_string_chain(),
+                        });
+                        continue;
+                    }
+                }
+                report.affected.push(comic_title);
+            }
+        }
+    }
+
+    Ok(report)
+}
+
+/// 下载收藏夹中的所有漫画；待处理的漫画id队列会持久化到磁盘(见[`FavoritesDownloadQueueStore`])，
+/// 应用在任务中途被关闭、或本次调用中途失败退出时，下次调用会从队列中剩余的漫画继续，
+/// 而不必重新下载一遍已经处理过的漫画；队列为空时(首次运行或上次已跑完)会重新拉取一遍当前收藏夹
+#[tauri::command(async)]
+#[specta::specta]
+pub async fn download_all_favorites(app: AppHandle, sort: Sort) -> CommandResult<BatchSummary> {
+    ensure_not_guest_mode(&app)?;
+    ensure_not_offline_mode(&app)?;
+    let pica_client = app.state::<Arc<dyn PicaApi>>().inner().clone();
+
+    let queue_state = app.state::<RwLock<FavoritesDownloadQueueStore>>();
+    let mut remaining = queue_state.read_or_panic().comic_ids();
+    if remaining.is_empty() {
+        remaining = fetch_all_favorite_comic_ids(&pica_client, sort).await?;
+        queue_state.write_or_panic().replace(&app, remaining.clone())?;
+    }
+
+    let mut summary = BatchSummary::default();
+    for comic_id in remaining {
+        match download_favorite_comic(&app, &comic_id).await {
+            Ok(comic_summary) => {
+                summary.succeeded += comic_summary.succeeded;
+                summary.skipped += comic_summary.skipped;
+                summary.failures.extend(comic_summary.failures);
+            }
+            Err(err) => summary.failures.push(BatchFailure {
+                item: comic_id.clone(),
+                reason: err.to_string(),
+            }),
+        }
+        queue_state.write_or_panic().remove(&app, &comic_id)?;
+    }
+    if let Err(err) = write_batch_report(&app, "download_all_favorites", &summary) {
+        crate::log::log_event(
+            &app,
+            &app.state::<FrontendLogState>(),
+            LogLevel::Warn,
+            format!("写入批量下载运行报告失败: {}", err.to_string_chain()),
+        );
+    }
+    Ok(summary)
+}
+
+/// 把批量操作的统计与失败明细写成一份带时间戳的运行报告(JSON+可读文本各一份)，
+/// 存放在`app_data_dir`下的`reports`目录，方便事后审计；写入失败只记日志，不影响批量操作本身的结果
+fn write_batch_report(app: &AppHandle, operation: &str, summary: &BatchSummary) -> anyhow::Result<()> {
+    let reports_dir = app
+        .path()
+        .app_data_dir()
+        .context("获取app_data_dir失败")?
+        .join("reports");
+    std::fs::create_dir_all(&reports_dir).context(format!("创建`{reports_dir:?}`失败"))?;
+
+    let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S");
+    let base_name = format!("{operation}_{timestamp}");
+
+    let json = serde_json::to_string_pretty(summary).context("序列化运行报告失败")?;
+    std::fs::write(reports_dir.join(format!("{base_name}.json")), json)
+        .context(format!("写入`{base_name}.json`失败"))?;
+
+    let mut text = format!(
+        "操作: {operation}\n时间: {timestamp}\n成功: {}\n跳过: {}\n失败: {}\n",
+        summary.succeeded,
+        summary.skipped,
+        summary.failures.len(),
+    );
+    if let Some(warning) = &summary.warning {
+        text.push_str(&format!("提示: {warning}\n"));
+    }
+    if !summary.failures.is_empty() {
+        text.push_str("\n失败明细:\n");
+        for failure in &summary.failures {
+            text.push_str(&format!("- {}: {}\n", failure.item, failure.reason));
+        }
+    }
+    std::fs::write(reports_dir.join(format!("{base_name}.txt")), text)
+        .context(format!("写入`{base_name}.txt`失败"))?;
+
+    Ok(())
+}
+
+/// 翻遍所有分页，收集当前哔咔收藏夹中所有漫画的id，供[`download_all_favorites`]逐本下载
+async fn fetch_all_favorite_comic_ids(
+    pica_client: &Arc<dyn PicaApi>,
+    sort: Sort,
+) -> anyhow::Result<Vec<String>> {
+    let mut ids = vec![];
+    let mut page = 1;
+    loop {
+        let pagination = pica_client.get_favorite_comics(sort, page).await?;
+        ids.extend(pagination.docs.iter().map(|c| c.id.clone()));
+        if page >= pagination.pages || pagination.docs.is_empty() {
+            break;
+        }
+        page += 1;
+    }
+    Ok(ids)
+}
+
+/// 下载单本收藏漫画的全部未下载章节，复用[`get_comic`]/[`download_episodes`]已有的逻辑，
+/// 供[`download_all_favorites`]逐本调用
+async fn download_favorite_comic(app: &AppHandle, comic_id: &str) -> CommandResult<BatchSummary> {
+    let comic = get_comic(
+        app.clone(),
+        app.state::<Arc<dyn PicaApi>>(),
+        comic_id.to_string(),
+    )
+    .await?;
+    let (downloaded, to_download): (Vec<Episode>, Vec<Episode>) =
+        comic.episodes.into_iter().partition(|ep| ep.is_downloaded);
+    let mut summary =
+        download_episodes(app.clone(), app.state::<DownloadManager>(), to_download).await?;
+    summary.skipped += downloaded.len() as u32;
+    Ok(summary)
+}
+
+/// 翻遍所有分页，收集当前哔咔收藏夹中所有漫画的标题(套用与本地目录命名一致的`filename_filter`)
+async fn fetch_all_favorite_titles(
+    app: &AppHandle,
+    pica_client: &Arc<dyn PicaApi>,
+) -> anyhow::Result<std::collections::HashSet<String>> {
+    let mut titles = std::collections::HashSet::new();
+    let mut page = 1;
+    loop {
+        let pagination = pica_client.get_favorite_comics(Sort::Default, page).await?;
+        for comic in &pagination.docs {
+            titles.insert(crate::utils::filename_filter(app, &comic.title));
+        }
+        if page >= pagination.pages || pagination.docs.is_empty() {
+            break;
+        }
+        page += 1;
+    }
+    Ok(titles)
+}
+
+/// 按标题精确搜索反查`comic_id`，只有唯一匹配时才返回`Some`，否则返回`None`交给调用方归入`unresolved`
+async fn resolve_comic_id_by_exact_title(
+    app: &AppHandle,
+    pica_client: &Arc<dyn PicaApi>,
+    comic_title: &str,
+) -> anyhow::Result<Option<String>> {
+    let pagination = pica_client
+        .search_comic(comic_title, Sort::Default, 1, vec![])
+        .await?;
+    let mut matches = pagination
+        .docs
+        .into_iter()
+        .filter(|comic| crate::utils::filename_filter(app, &comic.title) == comic_title);
+    let Some(first) = matches.next() else {
+        return Ok(None);
+    };
+    if matches.next().is_some() {
+        return Ok(None);
+    }
+    Ok(Some(first.id))
+}
+
+#[tauri::command(async)]
+#[specta::specta]
+#[allow(clippy::needless_pass_by_value)]
+pub fn analyze_disk_usage(config: State<RwLock<Config>>) -> CommandResult<Vec<ComicDiskUsage>> {
+    let download_dir = config.read_or_panic().download_dir.clone();
+    let mut usages = vec![];
+
+    let entries = std::fs::read_dir(&download_dir)
+        .map_err(|e| anyhow!("读取下载目录`{download_dir:?}`失败: {e}"))?;
+    for entry in entries {
+        let entry = entry.map_err(|e| anyhow!("读取下载目录`{download_dir:?}`的条目失败: {e}"))?;
+        let path = entry.path();
+        if !path.is_dir() {
+            continue;
+        }
+        let comic_title = entry.file_name().to_string_lossy().to_string();
+        let total_bytes =
+            dir_size(&path).map_err(|e| anyhow!("统计`{path:?}`的磁盘占用失败: {e}"))?;
+        let image_count = count_images(&path).map_err(|e| anyhow!("统计`{path:?}`的图片数量失败: {e}"))?;
+        let avg_image_bytes = if image_count == 0 {
+            0
+        } else {
+            total_bytes / image_count
+        };
+        usages.push(ComicDiskUsage {
+            comic_title,
+            total_bytes,
+            image_count,
+            avg_image_bytes,
+        });
+    }
+    // 按磁盘占用从大到小排序，方便用户决定删哪些大部头腾空间
+    usages.sort_by(|a, b| b.total_bytes.cmp(&a.total_bytes));
+
+    Ok(usages)
+}
+
+/// 统计下载目录内各图片格式的数量与体积占比，并抽样估算统一转为WebP大致能节省的空间，
+/// 作为[`transcode_downloaded`]的决策依据
+#[tauri::command(async)]
+#[specta::specta]
+#[allow(clippy::needless_pass_by_value)]
+pub fn analyze_image_formats(config: State<RwLock<Config>>) -> CommandResult<ImageFormatReport> {
+    let download_dir = config.read_or_panic().download_dir.clone();
+    let report = transcode::analyze_image_formats(&download_dir)
+        .map_err(|e| anyhow!("分析`{download_dir:?}`的图片格式失败: {e}"))?;
+    Ok(report)
+}
+
+#[tauri::command(async)]
+#[specta::specta]
+#[allow(clippy::needless_pass_by_value)]
+pub fn garbage_collect_library(
+    config: State<RwLock<Config>>,
+    clean: bool,
+) -> CommandResult<Vec<String>> {
+    if clean {
+        let config = config.read_or_panic();
+        if config.guest_mode {
+            return Err(anyhow!(crate::i18n::t(config.language, "guest_mode_disabled", &[])).into());
+        }
+    }
+    let (download_dir, temp_dir_prefix) = {
+        let config = config.read_or_panic();
+        (config.download_dir.clone(), config.temp_dir_prefix.clone())
+    };
+    let mut garbage_paths = vec![];
+
+    let comic_entries = std::fs::read_dir(&download_dir)
+        .map_err(|e| anyhow!("读取下载目录`{download_dir:?}`失败: {e}"))?;
+    for comic_entry in comic_entries {
+        let comic_entry =
+            comic_entry.map_err(|e| anyhow!("读取下载目录`{download_dir:?}`的条目失败: {e}"))?;
+        let comic_dir = comic_entry.path();
+        if !comic_dir.is_dir() {
+            continue;
+        }
+        // 漫画目录本身为空目录
+        if is_dir_empty(&comic_dir).map_err(|e| anyhow!("读取`{comic_dir:?}`失败: {e}"))? {
+            garbage_paths.push(comic_dir);
+            continue;
+        }
+
+        let ep_entries =
+            std::fs::read_dir(&comic_dir).map_err(|e| anyhow!("读取`{comic_dir:?}`失败: {e}"))?;
+        for ep_entry in ep_entries {
+            let ep_entry = ep_entry.map_err(|e| anyhow!("读取`{comic_dir:?}`的条目失败: {e}"))?;
+            let ep_dir = ep_entry.path();
+            if !ep_dir.is_dir() {
+                continue;
+            }
+            let ep_dir_name = ep_entry.file_name().to_string_lossy().to_string();
+            // 下载失败残留的临时目录，或没有任何图片的空章节目录，都算是不完整章节
+            let is_leftover_temp_dir = ep_dir_name.starts_with(&temp_dir_prefix);
+            let is_empty = is_dir_empty(&ep_dir).map_err(|e| anyhow!("读取`{ep_dir:?}`失败: {e}"))?;
+            if is_leftover_temp_dir || is_empty {
+                garbage_paths.push(ep_dir);
+            }
+        }
+    }
+
+    if clean {
+        for path in &garbage_paths {
+            std::fs::remove_dir_all(path).map_err(|e| anyhow!("删除`{path:?}`失败: {e}"))?;
+        }
+    }
+
+    let garbage_paths = garbage_paths
+        .into_iter()
+        .map(|p| p.to_string_lossy().to_string())
+        .collect();
+
+    Ok(garbage_paths)
+}
+
+#[tauri::command(async)]
+#[specta::specta]
+pub async fn health_check(
+    app: AppHandle,
+    config: State<'_, RwLock<Config>>,
+    pica_client: State<'_, Arc<dyn PicaApi>>,
+) -> CommandResult<Vec<HealthCheckItem>> {
+    const TOTAL: u32 = 4;
+    let mut items = vec![];
+
+    emit_health_check_progress(&app, "配置校验", 1, TOTAL);
+    items.push(check_config(&config));
+
+    emit_health_check_progress(&app, "临时目录清理", 2, TOTAL);
+    items.push(check_leftover_temp_dirs(&config));
+
+    emit_health_check_progress(&app, "库完整性校验", 3, TOTAL);
+    items.push(check_library_integrity(&config));
+
+    emit_health_check_progress(&app, "网络诊断", 4, TOTAL);
+    items.push(check_network(&pica_client).await);
+
+    Ok(items)
+}
+
+fn check_config(config: &State<RwLock<Config>>) -> HealthCheckItem {
+    let (download_dir, language) = {
+        let config = config.read_or_panic();
+        (config.download_dir.clone(), config.language)
+    };
+    match crate::config::check_dir_writable(&download_dir, language) {
+        Ok(()) => HealthCheckItem {
+            name: "配置校验".to_string(),
+            passed: true,
+            message: format!("下载目录`{download_dir:?}`存在且可写"),
+            suggestion: None,
+        },
+        Err((message, suggestion)) => HealthCheckItem {
+            name: "配置校验".to_string(),
+            passed: false,
+            message,
+            suggestion: Some(suggestion),
+        },
+    }
+}
+
+fn check_leftover_temp_dirs(config: &State<RwLock<Config>>) -> HealthCheckItem {
+    let (download_dir, temp_dir_prefix) = {
+        let config = config.read_or_panic();
+        (config.download_dir.clone(), config.temp_dir_prefix.clone())
+    };
+    let count = find_leftover_temp_dirs(&download_dir, &temp_dir_prefix)
+        .unwrap_or_default()
+        .len();
+    if count == 0 {
+        return HealthCheckItem {
+            name: "临时目录清理".to_string(),
+            passed: true,
+            message: "没有发现下载失败残留的临时目录".to_string(),
+            suggestion: None,
+        };
+    }
+    HealthCheckItem {
+        name: "临时目录清理".to_string(),
+        passed: false,
+        message: format!("发现`{count}`个下载失败残留的临时目录"),
+        suggestion: Some("可以调用`garbage_collect_library`清理这些临时目录".to_string()),
+    }
+}
+
+fn find_leftover_temp_dirs(
+    download_dir: &std::path::Path,
+    temp_dir_prefix: &str,
+) -> std::io::Result<Vec<PathBuf>> {
+    let mut leftover = vec![];
+    if !download_dir.exists() {
+        return Ok(leftover);
+    }
+    for comic_entry in std::fs::read_dir(download_dir)? {
+        let comic_dir = comic_entry?.path();
+        if !comic_dir.is_dir() {
+            continue;
+        }
+        for ep_entry in std::fs::read_dir(&comic_dir)? {
+            let ep_entry = ep_entry?;
+            let ep_dir_name = ep_entry.file_name().to_string_lossy().to_string();
+            if ep_entry.path().is_dir() && ep_dir_name.starts_with(temp_dir_prefix) {
+                leftover.push(ep_entry.path());
+            }
+        }
+    }
+    Ok(leftover)
+}
+
+fn check_library_integrity(config: &State<RwLock<Config>>) -> HealthCheckItem {
+    let download_dir = config.read_or_panic().download_dir.clone();
+    let mut empty_dir_count = 0;
+    if let Ok(comic_entries) = std::fs::read_dir(&download_dir) {
+        for comic_entry in comic_entries.flatten() {
+            let comic_dir = comic_entry.path();
+            if !comic_dir.is_dir() {
+                continue;
+            }
+            if is_dir_empty(&comic_dir).unwrap_or(false) {
+                empty_dir_count += 1;
+            }
+        }
+    }
+    if empty_dir_count == 0 {
+        return HealthCheckItem {
+            name: "库完整性校验".to_string(),
+            passed: true,
+            message: "没有发现空的漫画目录".to_string(),
+            suggestion: None,
+        };
+    }
+    HealthCheckItem {
+        name: "库完整性校验".to_string(),
+        passed: false,
+        message: format!("发现`{empty_dir_count}`个空的漫画目录"),
+        suggestion: Some("可以调用`garbage_collect_library`清理这些空目录".to_string()),
+    }
+}
+
+async fn check_network(pica_client: &State<'_, Arc<dyn PicaApi>>) -> HealthCheckItem {
+    match pica_client.get_user_profile().await {
+        Ok(_) => HealthCheckItem {
+            name: "网络诊断".to_string(),
+            passed: true,
+            message: "成功连接哔咔服务器".to_string(),
+            suggestion: None,
+        },
+        Err(err) => HealthCheckItem {
+            name: "网络诊断".to_string(),
+            passed: false,
+            message: format!("连接哔咔服务器失败: {}", err.to_string_chain()),
+            suggestion: Some("请检查网络连接、代理设置，或确认是否已登录".to_string()),
+        },
+    }
+}
+
+fn emit_health_check_progress(app: &AppHandle, item_name: &str, current: u32, total: u32) {
+    let payload = HealthCheckProgressEventPayload {
+        item_name: item_name.to_string(),
+        current,
+        total,
+    };
+    crate::events::emit_event(app, HealthCheckProgressEvent(payload));
+}
+
+#[tauri::command(async)]
+#[specta::specta]
+#[allow(clippy::needless_pass_by_value)]
+pub fn export_episode_as_cbz(
+    app: AppHandle,
+    config: State<RwLock<Config>>,
+    jobs: State<JobRegistry>,
+    comic: Comic,
+    ep: Episode,
+) -> CommandResult<Option<String>> {
+    ensure_not_guest_mode(&app)?;
+    let ep_dir = ep.dir_path(&app);
+    let temp_dir_prefix = config.read_or_panic().temp_dir_prefix.clone();
+    if !is_episode_complete(&ep_dir, &temp_dir_prefix) {
+        return Err(anyhow!("章节`{}`尚未下载完成，无法导出", ep.ep_title).into());
+    }
+    ensure_not_downloading(&jobs, &ep.ep_id, &ep.ep_title)?;
+
+    let path = export_cbz_to_file(
+        &app,
+        &config,
+        &ep_dir,
+        &ep.comic_title,
+        &ep.ep_title,
+        &ep.author,
+        &comic.chinese_team,
+        &comic.tags,
+        ep.order,
+    )?;
+    Ok(path.map(|p| p.to_string_lossy().to_string()))
+}
+
+/// 判断章节是否已完整下载：目录必须存在、不是以`temp_dir_prefix`开头的临时目录，且至少包含一张图片
+///
+/// 正在下载中的章节会先写入以`temp_dir_prefix`开头的临时目录，全部图片下载成功后才会重命名为正式目录，
+/// 所以这里不会把正在下载的章节误判为已完整下载；显式排除临时目录名是为了兼容下载中途崩溃后
+/// 残留在磁盘上、但已经写入部分图片的临时目录(见[`find_leftover_temp_dirs`])
+fn is_episode_complete(ep_dir: &std::path::Path, temp_dir_prefix: &str) -> bool {
+    let is_temp_dir = ep_dir
+        .file_name()
+        .is_some_and(|name| name.to_string_lossy().starts_with(temp_dir_prefix));
+    if is_temp_dir {
+        return false;
+    }
+    let Ok(mut entries) = std::fs::read_dir(ep_dir) else {
+        return false;
+    };
+    entries.any(|entry| entry.is_ok_and(|e| e.path().is_file()))
+}
+
+/// 检测`ep_id`对应的章节当前是否有活动的下载任务（复用[`JobRegistry`]，`job_id`为`ep_id`，
+/// 见`DownloadManager::process_episode`），有则拒绝导出，避免读到正在写入的不完整目录
+fn ensure_not_downloading(jobs: &JobRegistry, ep_id: &str, ep_title: &str) -> anyhow::Result<()> {
+    if jobs.is_active(ep_id) {
+        return Err(anyhow!("章节`{ep_title}`正在下载中，请等待下载完成后再导出"));
+    }
+    Ok(())
+}
+
+/// 为`ep`生成/复用本地缩略图缓存，长边不超过`max_edge`像素，返回缩略图本地路径列表，
+/// 供前端阅读器懒加载原图前先展示缩略图，避免直接加载大图时的卡顿；缩略图缓存到`app_cache_dir`下，
+/// 重复调用时已缓存的图片不会重新生成
+#[tauri::command(async)]
+#[specta::specta]
+#[allow(clippy::needless_pass_by_value)]
+pub fn get_chapter_thumbnails(
+    app: AppHandle,
+    config: State<RwLock<Config>>,
+    ep: Episode,
+    max_edge: u32,
+) -> CommandResult<Vec<String>> {
+    ensure_not_guest_mode(&app)?;
+    let ep_dir = ep.dir_path(&app);
+    let temp_dir_prefix = config.read_or_panic().temp_dir_prefix.clone();
+    if !is_episode_complete(&ep_dir, &temp_dir_prefix) {
+        return Err(anyhow!("章节`{}`尚未下载完成，无法生成缩略图", ep.ep_title).into());
+    }
+
+    let cache_dir = app
+        .path()
+        .app_cache_dir()
+        .map_err(|e| anyhow!("获取app_cache_dir失败: {e}"))?
+        .join("thumbnails")
+        .join(&ep.comic_title)
+        .join(&ep.ep_title);
+
+    let paths = thumbnails::generate_chapter_thumbnails(&ep_dir, &cache_dir, max_edge)
+        .map_err(|e| anyhow!("生成章节`{}`缩略图失败: {e}", ep.ep_title))?;
+    Ok(paths
+        .into_iter()
+        .map(|p| p.to_string_lossy().to_string())
+        .collect())
+}
+
+/// 把`ep_dir`下的所有图片打包为CBZ，返回实际写入的文件路径；如果按冲突策略应跳过，返回`None`
+///
+/// `Config.export_comic_info_xml`开启时，会额外写入一份`ComicInfo.xml`
+/// (见[`export::build_comic_info_xml`])，`comic_title`/`ep_title`/`author`/`chinese_team`/`tags`/`chapter_number`
+/// 即用于生成该文件
+#[allow(clippy::too_many_arguments)]
+fn export_cbz_to_file(
+    app: &AppHandle,
+    config: &State<RwLock<Config>>,
+    ep_dir: &PathBuf,
+    comic_title: &str,
+    ep_title: &str,
+    author: &str,
+    chinese_team: &str,
+    tags: &[String],
+    chapter_number: i64,
+) -> anyhow::Result<Option<PathBuf>> {
+    let output_path = ep_dir.with_extension("cbz");
+    let (policy, export_comic_info_xml) = {
+        let config = config.read_or_panic();
+        (config.export_conflict_policy, config.export_comic_info_xml)
+    };
+    let Some(output_path) = export::resolve_output_path(&output_path, policy) else {
+        emit_export_file_skipped(app, &output_path);
+        return Ok(None);
+    };
+
+    let file = std::fs::File::create(&output_path)
+        .map_err(|e| anyhow!("创建`{output_path:?}`失败: {e}"))?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options =
+        zip::write::SimpleFileOptions::default().compression_method(zip::CompressionMethod::Stored);
+
+    let mut entries: Vec<PathBuf> = std::fs::read_dir(ep_dir)
+        .map_err(|e| anyhow!("读取`{ep_dir:?}`失败: {e}"))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.is_file())
+        .collect();
+    entries.sort();
+
+    let grayscale_mode = config.read_or_panic().grayscale_mode;
+    let mut written_count = 0;
+    for path in entries {
+        let Some(file_name) = path.file_name().map(|n| n.to_string_lossy().to_string()) else {
+            continue;
+        };
+        zip.start_file(file_name, options)
+            .map_err(|e| anyhow!("写入`{output_path:?}`失败: {e}"))?;
+        let data = std::fs::read(&path).map_err(|e| anyhow!("读取`{path:?}`失败: {e}"))?;
+        let data = export::maybe_grayscale(&data, grayscale_mode)
+            .map_err(|e| anyhow!("将`{path:?}`转换为灰度失败: {e}"))?;
+        std::io::Write::write_all(&mut zip, &data)
+            .map_err(|e| anyhow!("写入`{output_path:?}`失败: {e}"))?;
+        written_count += 1;
+    }
+    if export_comic_info_xml {
+        let comic_info_xml = export::build_comic_info_xml(
+            comic_title,
+            ep_title,
+            author,
+            chinese_team,
+            tags,
+            chapter_number,
+            written_count,
+        );
+        zip.start_file("ComicInfo.xml", options)
+            .map_err(|e| anyhow!("写入`{output_path:?}`失败: {e}"))?;
+        std::io::Write::write_all(&mut zip, comic_info_xml.as_bytes())
+            .map_err(|e| anyhow!("写入`{output_path:?}`失败: {e}"))?;
+        written_count += 1;
+    }
+    zip.finish()
+        .map_err(|e| anyhow!("完成`{output_path:?}`的写入失败: {e}"))?;
+
+    if let Err(err) = export::verify_cbz(&output_path, written_count) {
+        let _ = std::fs::remove_file(&output_path);
+        return Err(anyhow!("导出的`{output_path:?}`校验未通过，已删除: {err}"));
+    }
+
+    Ok(Some(output_path))
+}
+
+/// 批量将多个章节导出为CBZ，导出前会校验每章是否已完整下载，
+/// 不完整/尚未下载的章节会被跳过并记录到返回的`BatchSummary.failures`中，不会中断整个批量操作
+#[tauri::command(async)]
+#[specta::specta]
+#[allow(clippy::needless_pass_by_value)]
+pub fn export_episodes_as_cbz(
+    app: AppHandle,
+    config: State<RwLock<Config>>,
+    jobs: State<JobRegistry>,
+    comic: Comic,
+    eps: Vec<Episode>,
+) -> CommandResult<BatchSummary> {
+    ensure_not_guest_mode(&app)?;
+    let temp_dir_prefix = config.read_or_panic().temp_dir_prefix.clone();
+    let mut summary = BatchSummary::default();
+    for ep in eps {
+        let ep_dir = ep.dir_path(&app);
+        if !is_episode_complete(&ep_dir, &temp_dir_prefix) {
+            summary.failures.push(BatchFailure {
+                item: ep.ep_title,
+                reason: "章节尚未下载完成，已跳过".to_string(),
+            });
+            continue;
+        }
+        if jobs.is_active(&ep.ep_id) {
+            summary.failures.push(BatchFailure {
+                item: ep.ep_title,
+                reason: "章节正在下载中，已跳过".to_string(),
+            });
+            continue;
+        }
+        match export_cbz_to_file(
+            &app,
+            &config,
+            &ep_dir,
+            &ep.comic_title,
+            &ep.ep_title,
+            &ep.author,
+            &comic.chinese_team,
+            &comic.tags,
+            ep.order,
+        ) {
+            Ok(Some(_)) => summary.succeeded += 1,
+            Ok(None) => summary.skipped += 1,
+            Err(err) => summary.failures.push(BatchFailure {
+                item: ep.ep_title,
+                reason: err.to_string_chain(),
+            }),
+        }
+    }
+    Ok(summary)
+}
+
+#[tauri::command(async)]
+#[specta::specta]
+#[allow(clippy::needless_pass_by_value)]
+pub fn export_episode_as_pdf(
+    app: AppHandle,
+    config: State<RwLock<Config>>,
+    jobs: State<JobRegistry>,
+    comic: Comic,
+    ep: Episode,
+) -> CommandResult<Option<String>> {
+    ensure_not_guest_mode(&app)?;
+    let ep_dir = ep.dir_path(&app);
+    let temp_dir_prefix = config.read_or_panic().temp_dir_prefix.clone();
+    if !is_episode_complete(&ep_dir, &temp_dir_prefix) {
+        return Err(anyhow!("章节`{}`尚未下载完成，无法导出", ep.ep_title).into());
+    }
+    ensure_not_downloading(&jobs, &ep.ep_id, &ep.ep_title)?;
+
+    let metadata = export::PdfMetadata {
+        title: comic.title,
+        author: comic.author,
+        subject: ep.ep_title.clone(),
+        keywords: comic.tags,
+    };
+    let path = export_pdf_to_file(&app, &config, &ep_dir, &metadata)?;
+    Ok(path.map(|p| p.to_string_lossy().to_string()))
+}
+
+/// 把`ep_dir`下的所有图片排版为PDF，返回实际写入的文件路径；如果按冲突策略应跳过，返回`None`
+fn export_pdf_to_file(
+    app: &AppHandle,
+    config: &State<RwLock<Config>>,
+    ep_dir: &PathBuf,
+    metadata: &export::PdfMetadata,
+) -> anyhow::Result<Option<PathBuf>> {
+    let output_path = ep_dir.with_extension("pdf");
+    let policy = config.read_or_panic().export_conflict_policy;
+    let Some(output_path) = export::resolve_output_path(&output_path, policy) else {
+        emit_export_file_skipped(app, &output_path);
+        return Ok(None);
+    };
+
+    let mut image_paths: Vec<PathBuf> = std::fs::read_dir(ep_dir)
+        .map_err(|e| anyhow!("读取`{ep_dir:?}`失败: {e}"))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.is_file())
+        .collect();
+    image_paths.sort();
+
+    let grayscale_mode = config.read_or_panic().grayscale_mode;
+    let overlay = config.read_or_panic().pdf_overlay.clone();
+    let pdf_bytes = export::images_to_pdf(&image_paths, metadata, grayscale_mode, &overlay)
+        .map_err(|e| anyhow!("将`{}`排版为PDF失败: {e}", metadata.subject))?;
+
+    std::fs::write(&output_path, pdf_bytes).map_err(|e| anyhow!("写入`{output_path:?}`失败: {e}"))?;
+
+    if let Err(err) = export::verify_pdf(&output_path, image_paths.len()) {
+        let _ = std::fs::remove_file(&output_path);
+        return Err(anyhow!("导出的`{output_path:?}`校验未通过，已删除: {err}"));
+    }
+
+    Ok(Some(output_path))
+}
+
+/// 批量将多个章节导出为PDF，导出前会校验每章是否已完整下载，
+/// 不完整/尚未下载的章节会被跳过并记录到返回的`BatchSummary.failures`中，不会中断整个批量操作
+#[tauri::command(async)]
+#[specta::specta]
+#[allow(clippy::needless_pass_by_value)]
+pub fn export_episodes_as_pdf(
+    app: AppHandle,
+    config: State<RwLock<Config>>,
+    jobs: State<JobRegistry>,
+    comic: Comic,
+    eps: Vec<Episode>,
+) -> CommandResult<BatchSummary> {
+    ensure_not_guest_mode(&app)?;
+    let temp_dir_prefix = config.read_or_panic().temp_dir_prefix.clone();
+    let mut summary = BatchSummary::default();
+    for ep in eps {
+        let ep_dir = ep.dir_path(&app);
+        if !is_episode_complete(&ep_dir, &temp_dir_prefix) {
+            summary.failures.push(BatchFailure {
+                item: ep.ep_title,
+                reason: "章节尚未下载完成，已跳过".to_string(),
+            });
+            continue;
+        }
+        if jobs.is_active(&ep.ep_id) {
+            summary.failures.push(BatchFailure {
+                item: ep.ep_title,
+                reason: "章节正在下载中，已跳过".to_string(),
+            });
+            continue;
+        }
+        let metadata = export::PdfMetadata {
+            title: comic.title.clone(),
+            author: comic.author.clone(),
+            subject: ep.ep_title.clone(),
+            keywords: comic.tags.clone(),
+        };
+        match export_pdf_to_file(&app, &config, &ep_dir, &metadata) {
+            Ok(Some(_)) => summary.succeeded += 1,
+            Ok(None) => summary.skipped += 1,
+            Err(err) => summary.failures.push(BatchFailure {
+                item: ep.ep_title,
+                reason: err.to_string_chain(),
+            }),
+        }
+    }
+    Ok(summary)
+}
+
+/// 把章节内的图片按阅读顺序纵向拼接导出为一张或多张长图，超过`max_height`(像素)时自动切分，
+/// 拼接过程中逐张上报`ExportLongStripProgressEvent`，返回实际写入的文件路径列表
+#[tauri::command(async)]
+#[specta::specta]
+#[allow(clippy::needless_pass_by_value)]
+pub fn export_episode_as_long_strip(
+    app: AppHandle,
+    config: State<RwLock<Config>>,
+    jobs: State<JobRegistry>,
+    ep: Episode,
+    max_height: u32,
+    format: export::LongStripFormat,
+) -> CommandResult<Vec<String>> {
+    ensure_not_guest_mode(&app)?;
+    let ep_dir = ep.dir_path(&app);
+    let temp_dir_prefix = config.read_or_panic().temp_dir_prefix.clone();
+    if !is_episode_complete(&ep_dir, &temp_dir_prefix) {
+        return Err(anyhow!("章节`{}`尚未下载完成，无法导出", ep.ep_title).into());
+    }
+    ensure_not_downloading(&jobs, &ep.ep_id, &ep.ep_title)?;
+
+    let mut image_paths: Vec<PathBuf> = std::fs::read_dir(&ep_dir)
+        .map_err(|e| anyhow!("读取`{ep_dir:?}`失败: {e}"))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.is_file())
+        .collect();
+    image_paths.sort();
+
+    let Some(output_dir) = ep_dir.parent() else {
+        return Err(anyhow!("无法获取`{ep_dir:?}`的父目录").into());
+    };
+    let grayscale_mode = config.read_or_panic().grayscale_mode;
+    let ep_title = ep.ep_title.clone();
+    let output_paths = export::export_long_strip(
+        &image_paths,
+        output_dir,
+        &ep_title,
+        max_height,
+        format,
+        grayscale_mode,
+        |current, total| {
+            emit_export_long_strip_progress(&app, &ep_title, current as u32, total as u32);
+        },
+    )
+    .map_err(|e| anyhow!("将`{ep_title}`导出为长图失败: {e}"))?;
+
+    Ok(output_paths
+        .into_iter()
+        .map(|p| p.to_string_lossy().to_string())
+        .collect())
+}
+
+fn emit_export_long_strip_progress(app: &AppHandle, ep_title: &str, current: u32, total: u32) {
+    let payload = ExportLongStripProgressEventPayload {
+        ep_title: ep_title.to_string(),
+        current,
+        total,
+    };
+    crate::events::emit_event(app, ExportLongStripProgressEvent(payload));
+}
+
+/// 把`comic`下已完整下载的多个章节合并导出为一本EPUB电子书，每章对应EPUB目录中的一个条目，
+/// 方便在电子书阅读器上连续阅读整本漫画；未完整下载/正在下载中的章节会被跳过，不会中断整体导出，
+/// 如果跳过后没有任何章节可用则报错。输出文件是漫画目录的同级文件(按漫画目录名命名)，而CBZ/PDF是章节目录的同级文件
+#[tauri::command(async)]
+#[specta::specta]
+#[allow(clippy::needless_pass_by_value)]
+pub fn export_comic_as_epub(
+    app: AppHandle,
+    config: State<RwLock<Config>>,
+    jobs: State<JobRegistry>,
+    comic: Comic,
+    eps: Vec<Episode>,
+) -> CommandResult<Option<String>> {
+    ensure_not_guest_mode(&app)?;
+    let Some(comic_dir) = eps
+        .first()
+        .map(|ep| ep.dir_path(&app))
+        .and_then(|ep_dir| ep_dir.parent().map(std::path::Path::to_path_buf))
+    else {
+        return Err(anyhow!("未提供任何章节，无法导出EPUB").into());
+    };
+
+    let temp_dir_prefix = config.read_or_panic().temp_dir_prefix.clone();
+    let mut chapters = vec![];
+    for ep in eps {
+        let ep_dir = ep.dir_path(&app);
+        if !is_episode_complete(&ep_dir, &temp_dir_prefix) || jobs.is_active(&ep.ep_id) {
+            continue;
+        }
+        let mut image_paths: Vec<PathBuf> = std::fs::read_dir(&ep_dir)
+            .map_err(|e| anyhow!("读取`{ep_dir:?}`失败: {e}"))?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| path.is_file())
+            .collect();
+        image_paths.sort();
+        chapters.push(export::EpubChapter {
+            title: ep.ep_title,
+            image_paths,
+        });
+    }
+    if chapters.is_empty() {
+        return Err(anyhow!("没有任何已完整下载且未在下载中的章节可导出").into());
+    }
+
+    let output_path = comic_dir.with_extension("epub");
+    let policy = config.read_or_panic().export_conflict_policy;
+    let Some(output_path) = export::resolve_output_path(&output_path, policy) else {
+        emit_export_file_skipped(&app, &output_path);
+        return Ok(None);
+    };
+
+    let chapter_count = chapters.len();
+    let metadata = export::EpubMetadata {
+        title: comic.title,
+        author: comic.author,
+        tags: comic.tags,
+    };
+    let epub_bytes = export::chapters_to_epub(&chapters, &metadata)
+        .map_err(|e| anyhow!("将`{}`打包为EPUB失败: {e}", metadata.title))?;
+
+    std::fs::write(&output_path, epub_bytes).map_err(|e| anyhow!("写入`{output_path:?}`失败: {e}"))?;
+
+    if let Err(err) = export::verify_epub(&output_path, chapter_count) {
+        let _ = std::fs::remove_file(&output_path);
+        return Err(anyhow!("导出的`{output_path:?}`校验未通过，已删除: {err}").into());
+    }
+
+    Ok(Some(output_path.to_string_lossy().to_string()))
+}
+
+/// 把`comic`下所有已完整下载的章节目录连同一份`metadata.json`(标题/作者/标签/简介/章节清单)
+/// 整体打包为单个zip，便于整本漫画的备份或转移；未完整下载/正在下载中的章节会被跳过，
+/// 每打包完一个章节就上报一次`ExportZipProgressEvent`
+#[tauri::command(async)]
+#[specta::specta]
+#[allow(clippy::needless_pass_by_value)]
+pub fn export_zip(
+    app: AppHandle,
+    config: State<RwLock<Config>>,
+    jobs: State<JobRegistry>,
+    comic: Comic,
+    eps: Vec<Episode>,
+) -> CommandResult<Option<String>> {
+    ensure_not_guest_mode(&app)?;
+    let Some(comic_dir) = eps
+        .first()
+        .map(|ep| ep.dir_path(&app))
+        .and_then(|ep_dir| ep_dir.parent().map(std::path::Path::to_path_buf))
+    else {
+        return Err(anyhow!("未提供任何章节，无法导出ZIP").into());
+    };
+
+    let temp_dir_prefix = config.read_or_panic().temp_dir_prefix.clone();
+    let ready_eps: Vec<Episode> = eps
+        .into_iter()
+        .filter(|ep| is_episode_complete(&ep.dir_path(&app), &temp_dir_prefix) && !jobs.is_active(&ep.ep_id))
+        .collect();
+    if ready_eps.is_empty() {
+        return Err(anyhow!("没有任何已完整下载且未在下载中的章节可导出").into());
+    }
+
+    let output_path = comic_dir.with_extension("zip");
+    let policy = config.read_or_panic().export_conflict_policy;
+    let Some(output_path) = export::resolve_output_path(&output_path, policy) else {
+        emit_export_file_skipped(&app, &output_path);
+        return Ok(None);
+    };
+
+    let metadata_json = serde_json::json!({
+        "title": comic.title,
+        "author": comic.author,
+        "tags": comic.tags,
+        "description": comic.description,
+        "chapters": ready_eps.iter().map(|ep| ep.ep_title.clone()).collect::<Vec<_>>(),
+        "exportedAt": Local::now().to_rfc3339(),
+    });
+    let metadata_bytes = serde_json::to_vec_pretty(&metadata_json)
+        .map_err(|e| anyhow!("序列化`metadata.json`失败: {e}"))?;
+
+    let file = std::fs::File::create(&output_path)
+        .map_err(|e| anyhow!("创建`{output_path:?}`失败: {e}"))?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options =
+        zip::write::SimpleFileOptions::default().compression_method(zip::CompressionMethod::Stored);
+
+    zip.start_file("metadata.json", options)
+        .map_err(|e| anyhow!("写入`{output_path:?}`失败: {e}"))?;
+    std::io::Write::write_all(&mut zip, &metadata_bytes)
+        .map_err(|e| anyhow!("写入`{output_path:?}`失败: {e}"))?;
+    let mut written_count = 1;
+
+    let total = ready_eps.len() as u32;
+    for (index, ep) in ready_eps.iter().enumerate() {
+        let ep_dir = ep.dir_path(&app);
+        let mut image_paths: Vec<PathBuf> = std::fs::read_dir(&ep_dir)
+            .map_err(|e| anyhow!("读取`{ep_dir:?}`失败: {e}"))?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| path.is_file())
+            .collect();
+        image_paths.sort();
+        for path in image_paths {
+            let Some(file_name) = path.file_name().map(|n| n.to_string_lossy().to_string()) else {
+                continue;
+            };
+            zip.start_file(format!("{}/{file_name}", ep.ep_title), options)
+                .map_err(|e| anyhow!("写入`{output_path:?}`失败: {e}"))?;
+            let data = std::fs::read(&path).map_err(|e| anyhow!("读取`{path:?}`失败: {e}"))?;
+            std::io::Write::write_all(&mut zip, &data)
+                .map_err(|e| anyhow!("写入`{output_path:?}`失败: {e}"))?;
+            written_count += 1;
+        }
+        emit_export_zip_progress(&app, &comic.title, index as u32 + 1, total);
+    }
+
+    zip.finish()
+        .map_err(|e| anyhow!("完成`{output_path:?}`的写入失败: {e}"))?;
+
+    if let Err(err) = export::verify_zip(&output_path, written_count) {
+        let _ = std::fs::remove_file(&output_path);
+        return Err(anyhow!("导出的`{output_path:?}`校验未通过，已删除: {err}").into());
+    }
+
+    Ok(Some(output_path.to_string_lossy().to_string()))
+}
+
+fn emit_export_zip_progress(app: &AppHandle, comic_title: &str, current: u32, total: u32) {
+    let payload = ExportZipProgressEventPayload {
+        comic_title: comic_title.to_string(),
+        current,
+        total,
+    };
+    crate::events::emit_event(app, ExportZipProgressEvent(payload));
+}
+
+#[tauri::command(async)]
+#[specta::specta]
+#[allow(clippy::needless_pass_by_value)]
+pub fn get_downloaded_comics(config: State<RwLock<Config>>) -> CommandResult<Vec<String>> {
+    let id_to_dir_map = create_comic_title_to_dir_map(&config)?;
+    let mut comic_titles: Vec<String> = id_to_dir_map.into_keys().collect();
+    comic_titles.sort();
+    Ok(comic_titles)
+}
+
+/// 导出本机已下载漫画的清单(标题+已完整下载的章节标题列表)，用于跨设备库同步：
+/// 在另一台设备上通过前端保存/传输该清单文件，再调用[`diff_with_manifest`]与本机库比对
+#[tauri::command(async)]
+#[specta::specta]
+#[allow(clippy::needless_pass_by_value)]
+pub fn export_library_manifest(config: State<RwLock<Config>>) -> CommandResult<LibraryManifest> {
+    let temp_dir_prefix = config.read_or_panic().temp_dir_prefix.clone();
+    let id_to_dir_map = create_comic_title_to_dir_map(&config)?;
+    let mut comics = vec![];
+    for (comic_title, library_dir) in id_to_dir_map {
+        let comic_dir = library_dir.join(&comic_title);
+        let mut ep_titles: Vec<String> = std::fs::read_dir(&comic_dir)
+            .map_err(|e| anyhow!("读取`{comic_dir:?}`失败: {e}"))?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| is_episode_complete(path, &temp_dir_prefix))
+            .filter_map(|path| path.file_name().map(|n| n.to_string_lossy().to_string()))
+            .collect();
+        ep_titles.sort();
+        comics.push(ComicManifestEntry {
+            comic_title,
+            ep_titles,
+        });
+    }
+    comics.sort_by(|a, b| a.comic_title.cmp(&b.comic_title));
+    Ok(LibraryManifest { comics })
+}
+
+/// 把导入的`manifest`(另一台设备导出的库清单)与本机库比对，返回本机缺少的章节，
+/// 即`manifest`中存在但本机没有或尚未完整下载的章节
+///
+/// 由于本地不持久化漫画/章节id(见[`LibraryManifest`])，这里只能提示"缺少哪些标题"，
+/// 无法在此直接发起补齐下载；调用方需要在有网络的前提下按标题重新搜索，找到对应漫画后
+/// 再调用[`download_episodes`]补齐
+#[tauri::command(async)]
+#[specta::specta]
+#[allow(clippy::needless_pass_by_value)]
+pub fn diff_with_manifest(
+    config: State<RwLock<Config>>,
+    manifest: LibraryManifest,
+) -> CommandResult<Vec<MissingEpisodes>> {
+    let local = export_library_manifest(config)?;
+    let local_map: std::collections::HashMap<&str, &Vec<String>> = local
+        .comics
+        .iter()
+        .map(|entry| (entry.comic_title.as_str(), &entry.ep_titles))
+        .collect();
+
+    let mut missing = vec![];
+    for entry in manifest.comics {
+        let local_ep_titles = local_map.get(entry.comic_title.as_str());
+        let missing_ep_titles: Vec<String> = entry
+            .ep_titles
+            .into_iter()
+            .filter(|ep_title| !local_ep_titles.is_some_and(|titles| titles.contains(ep_title)))
+            .collect();
+        if !missing_ep_titles.is_empty() {
+            missing.push(MissingEpisodes {
+                comic_title: entry.comic_title,
+                ep_titles: missing_ep_titles,
+            });
+        }
+    }
+    Ok(missing)
+}
+
+/// 统计某个分类/标签下，本地库相对线上的覆盖率：线上总数先按`term`当作分类搜索，
+/// 搜不到结果再退回把`term`当关键词搜索(覆盖标签场景，哔咔的搜索接口不单独支持按标签查询)；
+/// 本地侧统计已下载漫画中`info.txt`"标签:"一行包含`term`的数量(本地不持久化分类，只能用tags近似)
+#[tauri::command(async)]
+#[specta::specta]
+pub async fn coverage_report(
+    app: AppHandle,
+    pica_client: State<'_, Arc<dyn PicaApi>>,
+    config: State<'_, RwLock<Config>>,
+    term: String,
+) -> CommandResult<CoverageReport> {
+    ensure_not_offline_mode(&app)?;
+    let by_category = pica_client
+        .search_comic("", Sort::TimeNewest, 1, vec![term.clone()])
+        .await?;
+    let online_total = if by_category.total > 0 {
+        by_category.total
+    } else {
+        pica_client
+            .search_comic(&term, Sort::TimeNewest, 1, vec![])
+            .await?
+            .total
+    };
+
+    let library_dirs = config.read_or_panic().all_library_dirs();
+    let mut local_count = 0i64;
+    for library_dir in library_dirs {
+        if !library_dir.dir.exists() {
+            continue;
+        }
+        let entries = std::fs::read_dir(&library_dir.dir)
+            .map_err(|e| anyhow!("读取库分区`{:?}`失败: {e}", library_dir.dir))?;
+        for entry in entries {
+            let entry =
+                entry.map_err(|e| anyhow!("读取库分区`{:?}`的条目失败: {e}", library_dir.dir))?;
+            if !entry.path().is_dir() {
+                continue;
+            }
+            let info_path = entry.path().join("info.txt");
+            let Ok(content) = std::fs::read_to_string(&info_path) else {
+                continue;
+            };
+            let has_term = content
+                .lines()
+                .find(|line| line.starts_with("标签:"))
+                .is_some_and(|line| line.contains(&term));
+            if has_term {
+                local_count += 1;
+            }
+        }
+    }
+
+    let coverage_percent = if online_total > 0 {
+        local_count as f64 / online_total as f64 * 100.0
+    } else {
+        0.0
+    };
+
+    Ok(CoverageReport {
+        term,
+        online_total,
+        local_count,
+        coverage_percent,
+    })
+}
+
+#[tauri::command(async)]
+#[specta::specta]
+#[allow(clippy::needless_pass_by_value)]
+pub fn get_downloaded_comics_filtered(
+    config: State<RwLock<Config>>,
+    library_index: State<RwLock<LibraryIndex>>,
+    local_tag: Option<String>,
+    /// 按完结状态过滤，`None`表示不过滤
+    finished: Option<bool>,
+) -> CommandResult<Vec<PinyinGroup>> {
+    let mut comic_titles: Vec<String> = create_comic_title_to_dir_map(&config)?.into_keys().collect();
+    if let Some(local_tag) = local_tag {
+        let allowed = library_index.read_or_panic().filter_by_tag(&local_tag);
+        comic_titles.retain(|title| allowed.contains(title));
+    }
+    if let Some(finished) = finished {
+        let allowed = library_index.read_or_panic().filter_by_finished(finished);
+        comic_titles.retain(|title| allowed.contains(title));
+    }
+    Ok(group_by_pinyin(comic_titles))
+}
+
+#[tauri::command(async)]
+#[specta::specta]
+#[allow(clippy::needless_pass_by_value)]
+pub async fn merge_comic_versions(
+    app: AppHandle,
+    pica_client: State<'_, Arc<dyn PicaApi>>,
+    config: State<'_, RwLock<Config>>,
+    comic_id: String,
+) -> CommandResult<String> {
+    ensure_not_guest_mode(&app)?;
+    let comic = get_comic(app.clone(), pica_client, comic_id).await?;
+    // 磁盘上的目录名都经过`filename_filter`过滤，必须用过滤后的标题/作者才能匹配到真实目录，
+    // 否则标题/作者含有被过滤字符的漫画永远只能找到0个候选目录，见`Episode::comic_title`/`author`
+    let comic_title = crate::utils::filename_filter(&app, &comic.title);
+    let author = crate::utils::filename_filter(&app, &comic.author);
+    let comic_title_variants = [comic_title.clone(), format!("[{author}] {comic_title}")];
+
+    let candidate_dirs: Vec<PathBuf> = {
+        let library_dirs = config.read_or_panic().all_library_dirs();
+        library_dirs
+            .iter()
+            .flat_map(|library_dir| {
+                comic_title_variants
+                    .iter()
+                    .map(|title| library_dir.dir.join(title))
+            })
+            .filter(|dir| dir.is_dir())
+            .collect()
+    };
+    if candidate_dirs.len() < 2 {
+        return Err(anyhow!("漫画`{comic_title}`只找到一个版本目录，无需合并").into());
+    }
+
+    let dest_title = if config.read_or_panic().download_with_author {
+        format!("[{author}] {comic_title}")
+    } else {
+        comic_title.clone()
+    };
+    let dest_dir = config.read_or_panic().download_dir.join(&dest_title);
+    std::fs::create_dir_all(&dest_dir).map_err(|e| anyhow!("创建`{dest_dir:?}`失败: {e}"))?;
+
+    for source_dir in &candidate_dirs {
+        if *source_dir == dest_dir {
+            continue;
+        }
+        let episode_entries = std::fs::read_dir(source_dir)
+            .map_err(|e| anyhow!("读取`{source_dir:?}`失败: {e}"))?
+            .filter_map(std::result::Result::ok)
+            .filter(|entry| entry.path().is_dir());
+        for episode_entry in episode_entries {
+            let ep_title = episode_entry.file_name();
+            let source_ep_dir = episode_entry.path();
+            let dest_ep_dir = dest_dir.join(&ep_title);
+            // 同一章节在多个版本都存在时，保留图片数量更多(更完整)的那份
+            let source_count = count_images(&source_ep_dir).unwrap_or(0);
+            let dest_count = count_images(&dest_ep_dir).unwrap_or(0);
+            if source_count > dest_count {
+                if dest_ep_dir.exists() {
+                    std::fs::remove_dir_all(&dest_ep_dir)
+                        .map_err(|e| anyhow!("删除`{dest_ep_dir:?}`失败: {e}"))?;
+                }
+                move_dir(&source_ep_dir, &dest_ep_dir)
+                    .map_err(|e| anyhow!("合并`{source_ep_dir:?}`失败: {e}"))?;
+            }
+        }
+        if is_dir_empty(source_dir).unwrap_or(false) {
+            let _ = std::fs::remove_dir(source_dir);
+        } else {
+            let _ = std::fs::remove_dir_all(source_dir);
+        }
+    }
+
+    Ok(dest_dir.to_string_lossy().to_string())
+}
+
+/// 把`src`目录移动到`dst`，优先用`rename`(同一文件系统下是原子的)，
+/// 跨文件系统导致`rename`失败时退回到递归复制+删除源目录
+fn move_dir(src: &std::path::Path, dst: &std::path::Path) -> std::io::Result<()> {
+    if std::fs::rename(src, dst).is_ok() {
+        return Ok(());
+    }
+    std::fs::create_dir_all(dst)?;
+    for entry in std::fs::read_dir(src)?.filter_map(std::result::Result::ok) {
+        let dst_path = dst.join(entry.file_name());
+        if entry.path().is_dir() {
+            move_dir(&entry.path(), &dst_path)?;
+        } else {
+            std::fs::copy(entry.path(), &dst_path)?;
+        }
+    }
+    std::fs::remove_dir_all(src)
+}
+
+/// 聚合所有库分区，建立`漫画标题 -> 所在库分区目录`的映射
+///
+/// 同一漫画标题在多个库分区都存在时，取第一个找到的库分区
+fn create_comic_title_to_dir_map(
+    config: &State<RwLock<Config>>,
+) -> CommandResult<std::collections::HashMap<String, PathBuf>> {
+    let library_dirs = config.read_or_panic().all_library_dirs();
+    let mut map = std::collections::HashMap::new();
+    for library_dir in library_dirs {
+        if !library_dir.dir.exists() {
+            continue;
+        }
+        let entries = std::fs::read_dir(&library_dir.dir)
+            .map_err(|e| anyhow!("读取库分区`{:?}`失败: {e}", library_dir.dir))?;
+        for entry in entries {
+            let entry = entry.map_err(|e| anyhow!("读取库分区`{:?}`的条目失败: {e}", library_dir.dir))?;
+            if !entry.path().is_dir() {
+                continue;
+            }
+            let comic_title = entry.file_name().to_string_lossy().to_string();
+            map.entry(comic_title).or_insert(library_dir.dir.clone());
+        }
+    }
+    Ok(map)
+}
+
+/// 按当前`filename_filter_rules`重新生成所有库分区下漫画目录及其章节目录的名称，
+/// 并将改名不一致的目录重命名为最新规则下的名称，用于规则变更后迁移历史下载
+///
+/// 由于磁盘上只保留过滤后的目录名(原始标题未单独存储)，这里是对已过滤的名称再次应用当前规则，
+/// 对于新增的emoji移除/全角转半角等规则是等价的，已满足内置非法字符规则的名称不受影响
+#[tauri::command(async)]
+#[specta::specta]
+#[allow(clippy::needless_pass_by_value)]
+pub fn migrate_filenames(
+    app: AppHandle,
+    config: State<RwLock<Config>>,
+) -> CommandResult<BatchSummary> {
+    ensure_not_guest_mode(&app)?;
+    let mut summary = BatchSummary::default();
+    let library_dirs = config.read_or_panic().all_library_dirs();
+    for library_dir in library_dirs {
+        if !library_dir.dir.exists() {
+            continue;
+        }
+        let comic_dirs: Vec<PathBuf> = match std::fs::read_dir(&library_dir.dir) {
+            Ok(entries) => entries
+                .filter_map(|entry| entry.ok().map(|e| e.path()))
+                .filter(|path| path.is_dir())
+                .collect(),
+            Err(err) => {
+                summary.failures.push(BatchFailure {
+                    item: library_dir.dir.to_string_lossy().to_string(),
+                    reason: err.to_string(),
+                });
+                continue;
+            }
+        };
+        for comic_dir in comic_dirs {
+            migrate_dir_name(&app, &comic_dir, &mut summary);
+            let Ok(ep_dirs) = std::fs::read_dir(&comic_dir) else {
+                continue;
+            };
+            for ep_dir in ep_dirs.filter_map(|entry| entry.ok().map(|e| e.path())) {
+                if ep_dir.is_dir() {
+                    migrate_dir_name(&app, &ep_dir, &mut summary);
+                }
+            }
+        }
+    }
+    Ok(summary)
+}
+
+/// 如果`dir`的目录名按当前规则重新过滤后发生了变化，就将其重命名为新名称
+fn migrate_dir_name(app: &AppHandle, dir: &std::path::Path, summary: &mut BatchSummary) {
+    let Some(old_name) = dir.file_name().map(|n| n.to_string_lossy().to_string()) else {
+        return;
+    };
+    let new_name = crate::utils::filename_filter(app, &old_name);
+    if new_name == old_name {
+        summary.skipped += 1;
+        return;
+    }
+    let Some(parent) = dir.parent() else {
+        summary.failures.push(BatchFailure {
+            item: old_name,
+            reason: "无法获取父目录".to_string(),
+        });
+        return;
+    };
+    let new_path = parent.join(&new_name);
+    if new_path.exists() {
+        summary.failures.push(BatchFailure {
+            item: old_name,
+            reason: format!("目标路径`{new_path:?}`已存在，已跳过"),
+        });
+        return;
+    }
+    match move_dir(dir, &new_path) {
+        Ok(()) => summary.succeeded += 1,
+        Err(err) => summary.failures.push(BatchFailure {
+            item: old_name,
+            reason: err.to_string(),
+        }),
+    }
+}
+
+/// 将`library_index_filename`配置从默认值`library_index.json`改名后，把旧文件迁移为新文件名
+///
+/// `library_index.json`本身存放在`app_data_dir`而非各漫画目录内，不受下载目录所在文件系统/同步盘的影响，
+/// 但部分用户仍希望自定义该文件名以配合自己的备份/同步策略，故提供此迁移入口
+#[tauri::command(async)]
+#[specta::specta]
+#[allow(clippy::needless_pass_by_value)]
+pub fn migrate_library_index_filename(
+    app: AppHandle,
+    config: State<RwLock<Config>>,
+) -> CommandResult<String> {
+    ensure_not_guest_mode(&app)?;
+    let filename = config.read_or_panic().library_index_filename.clone();
+    let message = LibraryIndex::migrate_filename(&app, &filename)?;
+    Ok(message)
+}
+
+/// 设置前端订阅日志事件的最低级别，并控制是否暂停日志推送
+///
+/// `LogEvent`现在由后端按此设置过滤后才`emit`，避免日志量大(如批量下载报错刷屏)时
+/// 频繁的IPC调用卡顿前端界面；`paused`为`true`时即使级别达标也不会推送，直到再次调用恢复
+#[tauri::command(async)]
+#[specta::specta]
+#[allow(clippy::needless_pass_by_value)]
+pub fn set_frontend_log_level(
+    log_state: State<FrontendLogState>,
+    level: LogLevel,
+    paused: bool,
+) -> CommandResult<()> {
+    log_state.set_min_level(level);
+    log_state.set_paused(paused);
+    Ok(())
+}
+
+/// 把`comic_title`下所有已下载的图片重新转码为`target_format`，用于`download_dir`中存在
+/// 更改下载设置前下载的旧格式图片时，不需要重新下载即可统一格式
+///
+/// 库中的漫画目录按标题而非id命名（见[`create_comic_title_to_dir_map`]），因此用`comic_title`
+/// 而非请求里提到的`comic_id`定位目录，与[`get_downloaded_comics`]等本地操作的command保持一致
+#[tauri::command(async)]
+#[specta::specta]
+#[allow(clippy::needless_pass_by_value)]
+pub fn transcode_downloaded(
+    app: AppHandle,
+    config: State<RwLock<Config>>,
+    jobs: State<JobRegistry>,
+    comic_title: String,
+    target_format: TranscodeFormat,
+) -> CommandResult<BatchSummary> {
+    ensure_not_guest_mode(&app)?;
+    let id_to_dir_map = create_comic_title_to_dir_map(&config)?;
+    let Some(library_dir) = id_to_dir_map.get(&comic_title) else {
+        return Err(anyhow!("未找到漫画`{comic_title}`的下载目录").into());
+    };
+    let comic_dir = library_dir.join(&comic_title);
+
+    // 用`comic_title`作为`job_id`，可通过`cancel_job`取消，已分发给线程池的图片仍会转码完成
+    let cancel_flag = jobs.register(&comic_title);
+    let result = transcode::transcode_comic(&comic_dir, target_format, &cancel_flag, |current, total| {
+        emit_transcode_progress(&app, &comic_title, current, total);
+    });
+    jobs.finish(&comic_title);
+
+    let summary = result.map_err(|e| anyhow!("转码漫画`{comic_title}`失败: {e}"))?;
+    Ok(summary)
+}
+
+fn emit_transcode_progress(app: &AppHandle, comic_title: &str, current: u32, total: u32) {
+    let payload = TranscodeProgressEventPayload {
+        comic_title: comic_title.to_string(),
+        current,
+        total,
+    };
+    crate::events::emit_event(app, TranscodeProgressEvent(payload));
+}
+
+/// 请求取消指定`job_id`的长任务（例如章节下载的`ep_id`、转码的`comic_title`），
+/// 返回该任务是否存在；已经开始执行的工作不会被中途打断，只影响尚未开始的后续步骤
+#[tauri::command(async)]
+#[specta::specta]
+#[allow(clippy::needless_pass_by_value)]
+pub fn cancel_job(jobs: State<JobRegistry>, job_id: String) -> CommandResult<bool> {
+    Ok(jobs.cancel(&job_id))
+}
+
+/// 把各库分区(`download_dir`及`library_dirs`)下满足条件的漫画自动归档为CBZ并写入`config.archive_dir`：目录下所有文件的最后修改时间
+/// 距今已超过`min_age_days`天(即`min_age_days`天内都未曾再下载/修改过)，则将整本漫画(所有章节)
+/// 打包为一个CBZ写入归档目录，`delete_source`为`true`时归档成功后删除原图片目录以释放空间
+///
+/// `max_size_mb`为`Some`时，单个CBZ体积超过该阈值就按章节边界拆分为多卷(`{comic_title} 第01卷.cbz`等)，
+/// 每新增一卷都会发出一次[`ArchiveVolumeCreatedEvent`]；为`None`时保持归档为单个CBZ不拆分
+///
+/// 本地标签/评分([`LibraryIndex`])与阅读进度([`ReadingProgressStore`])都以`comic_title`为键
+/// 单独持久化，不存放在图片目录内，因此删除原图片目录不会丢失这些元数据
+///
+/// 不记录下载完成的时间戳，而是直接比较文件的修改时间，这样重新下载/续传过的漫画会因为
+/// 修改时间被刷新而自动排除在本次归档范围之外，不需要额外维护状态。适合配合外部调度器定期调用
+///
+/// `only_finished`为`true`时只归档完结漫画([`LocalComicMeta::finished`])，避免连载中的漫画
+/// 被提前搬进归档目录后不再随新章节更新
+#[tauri::command(async)]
+#[specta::specta]
+#[allow(clippy::needless_pass_by_value)]
+pub fn auto_archive(
+    app: AppHandle,
+    config: State<RwLock<Config>>,
+    library_index: State<RwLock<LibraryIndex>>,
+    min_age_days: u32,
+    delete_source: bool,
+    max_size_mb: Option<u64>,
+    only_finished: bool,
+) -> CommandResult<BatchSummary> {
+    ensure_not_guest_mode(&app)?;
+    let archive_dir = config
+        .read_or_panic()
+        .archive_dir
+        .clone()
+        .ok_or_else(|| anyhow!("尚未配置归档目录"))?;
+    std::fs::create_dir_all(&archive_dir)
+        .map_err(|e| anyhow!("创建归档目录`{archive_dir:?}`失败: {e}"))?;
+
+    let min_age = Duration::from_secs(u64::from(min_age_days) * 24 * 60 * 60);
+    let now = SystemTime::now();
+    let policy = config.read_or_panic().export_conflict_policy;
+    let grayscale_mode = config.read_or_panic().grayscale_mode;
+
+    let mut summary = BatchSummary::default();
+    for (comic_title, library_dir) in create_comic_title_to_dir_map(&config)? {
+        if only_finished && library_index.read_or_panic().get(&comic_title).finished != Some(true) {
+            summary.skipped += 1;
+            continue;
+        }
+        let comic_dir = library_dir.join(&comic_title);
+        let last_modified = match latest_mtime(&comic_dir) {
+            Ok(Some(t)) => t,
+            Ok(None) => continue, // 空目录，没有可归档的内容
+            Err(err) => {
+                summary.failures.push(BatchFailure {
+                    item: comic_title,
+                    reason: err.to_string_chain(),
+                });
+                continue;
+            }
+        };
+        if now.duration_since(last_modified).unwrap_or_default() < min_age {
+            summary.skipped += 1;
+            continue;
+        }
+
+        let output_paths = match archive_comic_to_cbz(
+            &app,
+            &comic_title,
+            &comic_dir,
+            &archive_dir,
+            policy,
+            grayscale_mode,
+            max_size_mb,
+        ) {
+            Ok(paths) => paths,
+            Err(err) => {
+                summary.failures.push(BatchFailure {
+                    item: comic_title,
+                    reason: err.to_string_chain(),
+                });
+                continue;
+            }
+        };
+        if output_paths.is_empty() {
+            summary.skipped += 1;
+            continue;
+        }
+
+        if delete_source {
+            if let Err(err) = std::fs::remove_dir_all(&comic_dir) {
+                summary.failures.push(BatchFailure {
+                    item: comic_title,
+                    reason: format!("已归档到{output_paths:?}，但删除原目录失败: {err}"),
+                });
+                continue;
+            }
+        }
+        summary.succeeded += 1;
+    }
+
+    Ok(summary)
+}
+
+/// 递归查找`dir`下所有文件中最新的修改时间，`dir`下没有任何文件时返回`None`
+fn latest_mtime(dir: &std::path::Path) -> anyhow::Result<Option<SystemTime>> {
+    let mut latest = None;
+    for entry in std::fs::read_dir(dir).map_err(|e| anyhow!("读取`{dir:?}`失败: {e}"))? {
+        let entry = entry.map_err(|e| anyhow!("读取`{dir:?}`的条目失败: {e}"))?;
+        let path = entry.path();
+        let candidate = if path.is_dir() {
+            latest_mtime(&path)?
+        } else {
+            Some(
+                entry
+                    .metadata()
+                    .map_err(|e| anyhow!("读取`{path:?}`的元数据失败: {e}"))?
+                    .modified()
+                    .map_err(|e| anyhow!("读取`{path:?}`的修改时间失败: {e}"))?,
+            )
+        };
+        if candidate > latest {
+            latest = candidate;
+        }
+    }
+    Ok(latest)
+}
+
+/// 单个章节目录及其按文件名排序后的图片路径，`size`为这些图片的原始文件体积之和(字节)，
+/// 用于按体积拆分多卷时估算每卷体积(zip内实际写入的是灰度转换后的数据，与`size`会有偏差，
+/// 但作为拆分依据已经足够，避免为了精确计量而提前对所有图片做一遍灰度转换)
+struct ArchiveEpisode {
+    dir_name: String,
+    image_paths: Vec<PathBuf>,
+    size: u64,
+}
+
+/// 读取`comic_dir`下所有章节目录，按目录名排序，并统计每个章节的图片体积
+fn collect_archive_episodes(comic_dir: &std::path::Path) -> anyhow::Result<Vec<ArchiveEpisode>> {
+    let mut ep_dirs: Vec<PathBuf> = std::fs::read_dir(comic_dir)
+        .map_err(|e| anyhow!("读取`{comic_dir:?}`失败: {e}"))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.is_dir())
+        .collect();
+    ep_dirs.sort();
+
+    let mut episodes = Vec::with_capacity(ep_dirs.len());
+    for ep_dir in ep_dirs {
+        let Some(dir_name) = ep_dir.file_name().map(|n| n.to_string_lossy().to_string()) else {
+            continue;
+        };
+        let mut image_paths: Vec<PathBuf> = std::fs::read_dir(&ep_dir)
+            .map_err(|e| anyhow!("读取`{ep_dir:?}`失败: {e}"))?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| path.is_file())
+            .collect();
+        image_paths.sort();
+
+        let mut size = 0;
+        for path in &image_paths {
+            size += std::fs::metadata(path)
+                .map_err(|e| anyhow!("读取`{path:?}`的元数据失败: {e}"))?
+                .len();
+        }
+        episodes.push(ArchiveEpisode {
+            dir_name,
+            image_paths,
+            size,
+        });
+    }
+    Ok(episodes)
+}
+
+/// 按`max_size_mb`把`episodes`分组为多卷：按章节顺序累加体积，新增一个章节会导致当前卷超过阈值时，
+/// 就从下一个章节开始新的一卷；为保证进度，单个章节自身已超过阈值时仍独占一卷，不再继续拆分该章节
+fn split_into_volumes(episodes: Vec<ArchiveEpisode>, max_size_mb: Option<u64>) -> Vec<Vec<ArchiveEpisode>> {
+    let Some(max_size_mb) = max_size_mb else {
+        return vec![episodes];
+    };
+    let max_bytes = max_size_mb * 1024 * 1024;
+
+    let mut volumes: Vec<Vec<ArchiveEpisode>> = vec![];
+    let mut current: Vec<ArchiveEpisode> = vec![];
+    let mut current_size: u64 = 0;
+    for episode in episodes {
+        if !current.is_empty() && current_size + episode.size > max_bytes {
+            volumes.push(std::mem::take(&mut current));
+            current_size = 0;
+        }
+        current_size += episode.size;
+        current.push(episode);
+    }
+    if !current.is_empty() {
+        volumes.push(current);
+    }
+    volumes
+}
+
+/// 把`comic_dir`下所有章节目录打包为CBZ写入`archive_dir`，zip内以`章节目录名/图片文件名`保持章节分组
+///
+/// `max_size_mb`为`Some`且总体积超过该阈值时，按章节边界拆分为多卷，文件名形如
+/// `{comic_title} 第01卷.cbz`；未超过阈值或`max_size_mb`为`None`时只产出`{comic_title}.cbz`一个文件。
+/// 每写完一卷都会发出一次[`ArchiveVolumeCreatedEvent`]；任意一卷因`policy`为[`ExportConflictPolicy::Skip`]
+/// 而与已有文件冲突时，视为整本漫画本次跳过，返回空列表
+fn archive_comic_to_cbz(
+    app: &AppHandle,
+    comic_title: &str,
+    comic_dir: &std::path::Path,
+    archive_dir: &std::path::Path,
+    policy: export::ExportConflictPolicy,
+    grayscale_mode: export::GrayscaleMode,
+    max_size_mb: Option<u64>,
+) -> anyhow::Result<Vec<PathBuf>> {
+    let episodes = collect_archive_episodes(comic_dir)?;
+    let volumes = split_into_volumes(episodes, max_size_mb);
+    let volume_count = volumes.len();
+
+    let mut output_paths = Vec::with_capacity(volume_count);
+    for (i, episodes) in volumes.into_iter().enumerate() {
+        let file_name = if volume_count > 1 {
+            format!("{comic_title} 第{:02}卷.cbz", i + 1)
+        } else {
+            format!("{comic_title}.cbz")
+        };
+        let Some(output_path) = export::resolve_output_path(&archive_dir.join(file_name), policy)
+        else {
+            for path in &output_paths {
+                let _ = std::fs::remove_file(path);
+            }
+            return Ok(vec![]);
+        };
+
+        write_archive_volume(&output_path, &episodes, grayscale_mode)?;
+        crate::events::emit_event(
+            app,
+            ArchiveVolumeCreatedEvent(ArchiveVolumeCreatedEventPayload {
+                comic_title: comic_title.to_string(),
+                volume_index: u32::try_from(i + 1).unwrap_or(u32::MAX),
+                path: output_path.to_string_lossy().to_string(),
+            }),
+        );
+        output_paths.push(output_path);
+    }
+
+    Ok(output_paths)
+}
+
+/// 把一卷包含的所有章节写入一个CBZ文件
+fn write_archive_volume(
+    output_path: &std::path::Path,
+    episodes: &[ArchiveEpisode],
+    grayscale_mode: export::GrayscaleMode,
+) -> anyhow::Result<()> {
+    let file = std::fs::File::create(output_path)
+        .map_err(|e| anyhow!("创建`{output_path:?}`失败: {e}"))?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options =
+        zip::write::SimpleFileOptions::default().compression_method(zip::CompressionMethod::Stored);
+
+    let mut written_count = 0;
+    for episode in episodes {
+        for path in &episode.image_paths {
+            let Some(file_name) = path.file_name().map(|n| n.to_string_lossy().to_string()) else {
+                continue;
+            };
+            zip.start_file(format!("{}/{file_name}", episode.dir_name), options)
+                .map_err(|e| anyhow!("写入`{output_path:?}`失败: {e}"))?;
+            let data = std::fs::read(path).map_err(|e| anyhow!("读取`{path:?}`失败: {e}"))?;
+            let data = export::maybe_grayscale(&data, grayscale_mode)
+                .map_err(|e| anyhow!("将`{path:?}`转换为灰度失败: {e}"))?;
+            std::io::Write::write_all(&mut zip, &data)
+                .map_err(|e| anyhow!("写入`{output_path:?}`失败: {e}"))?;
+            written_count += 1;
+        }
+    }
+
+    zip.finish()
+        .map_err(|e| anyhow!("完成`{output_path:?}`的写入失败: {e}"))?;
+
+    if let Err(err) = export::verify_cbz(output_path, written_count) {
+        let _ = std::fs::remove_file(output_path);
+        return Err(anyhow!("导出的`{output_path:?}`校验未通过，已删除: {err}"));
+    }
+
+    Ok(())
+}
+
+#[tauri::command(async)]
+#[specta::specta]
+#[allow(clippy::needless_pass_by_value)]
+pub fn add_to_wishlist(
+    app: AppHandle,
+    wishlist: State<RwLock<Wishlist>>,
+    comic: Comic,
+) -> CommandResult<()> {
+    ensure_not_guest_mode(&app)?;
+    let item = WishlistItem {
+        comic_id: comic.id,
+        title: comic.title,
+        author: comic.author,
+        thumb: comic.thumb,
+    };
+    let mut wishlist = wishlist.write_or_panic();
+    wishlist.add(item);
+    wishlist.save(&app)?;
+    Ok(())
+}
+
+#[tauri::command(async)]
+#[specta::specta]
+#[allow(clippy::needless_pass_by_value)]
+pub fn remove_from_wishlist(
+    app: AppHandle,
+    wishlist: State<RwLock<Wishlist>>,
+    comic_id: String,
+) -> CommandResult<()> {
+    ensure_not_guest_mode(&app)?;
+    let mut wishlist = wishlist.write_or_panic();
+    wishlist.remove(&comic_id);
+    wishlist.save(&app)?;
+    Ok(())
+}
+
+#[tauri::command(async)]
+#[specta::specta]
+#[allow(clippy::needless_pass_by_value)]
+pub fn get_wishlist(wishlist: State<RwLock<Wishlist>>) -> CommandResult<Vec<WishlistItem>> {
+    Ok(wishlist.read_or_panic().items())
+}
+
+#[tauri::command(async)]
+#[specta::specta]
+#[allow(clippy::needless_pass_by_value)]
+pub fn add_tag_subscription(
+    app: AppHandle,
+    tag_subscriptions: State<RwLock<TagSubscriptionStore>>,
+    tag: String,
+) -> CommandResult<()> {
+    ensure_not_guest_mode(&app)?;
+    let mut tag_subscriptions = tag_subscriptions.write_or_panic();
+    tag_subscriptions.add(tag);
+    tag_subscriptions.save(&app)?;
+    Ok(())
+}
+
+#[tauri::command(async)]
+#[specta::specta]
+#[allow(clippy::needless_pass_by_value)]
+pub fn remove_tag_subscription(
+    app: AppHandle,
+    tag_subscriptions: State<RwLock<TagSubscriptionStore>>,
+    tag: String,
+) -> CommandResult<()> {
+    ensure_not_guest_mode(&app)?;
+    let mut tag_subscriptions = tag_subscriptions.write_or_panic();
+    tag_subscriptions.remove(&tag);
+    tag_subscriptions.save(&app)?;
+    Ok(())
+}
+
+#[tauri::command(async)]
+#[specta::specta]
+#[allow(clippy::needless_pass_by_value)]
+pub fn get_tag_subscriptions(
+    tag_subscriptions: State<RwLock<TagSubscriptionStore>>,
+) -> CommandResult<Vec<TagSubscription>> {
+    Ok(tag_subscriptions.read_or_panic().subscriptions())
+}
+
+/// 手动触发一次tag订阅检查，行为与后台调度器([`run_tag_subscription_scheduler`])一致，
+/// 用于前端提供"立即检查"按钮，而不必等待下一次自动轮询
+#[tauri::command(async)]
+#[specta::specta]
+pub async fn check_tag_subscriptions(
+    app: AppHandle,
+    pica_client: State<'_, Arc<dyn PicaApi>>,
+    download_manager: State<'_, DownloadManager>,
+) -> CommandResult<()> {
+    ensure_not_guest_mode(&app)?;
+    ensure_not_offline_mode(&app)?;
+    let pica_client = pica_client.inner().clone();
+    let download_manager = download_manager.inner().clone();
+    check_tag_subscriptions_once(&app, &pica_client, &download_manager).await?;
+    Ok(())
+}
+
+/// tag订阅后台调度器：按`Config.tag_subscription_check_interval_secs`周期性检查所有已订阅的tag，
+/// 发现新作时发出[`NewTagComicFoundEvent`]，并按`Config.tag_subscription_auto_download_sample`
+/// 决定是否自动下载新作第一章供试读
+pub(crate) async fn run_tag_subscription_scheduler(app: AppHandle) {
+    let pica_client = app.state::<Arc<dyn PicaApi>>().inner().clone();
+    let download_manager = app.state::<DownloadManager>().inner().clone();
+    loop {
+        let interval_secs = app
+            .state::<RwLock<Config>>()
+            .read_or_panic()
+            .tag_subscription_check_interval_secs
+            .max(1);
+        tokio::time::sleep(Duration::from_secs(interval_secs)).await;
+        if let Err(err) = check_tag_subscriptions_once(&app, &pica_client, &download_manager).await {
+            eprintln!("warn: tag订阅检查失败: {err}");
+        }
+    }
+}
+
+/// 对所有已订阅的tag各搜索一次`TimeNewest`结果，与上次记录的id比对找出新作
+async fn check_tag_subscriptions_once(
+    app: &AppHandle,
+    pica_client: &Arc<dyn PicaApi>,
+    download_manager: &DownloadManager,
+) -> anyhow::Result<()> {
+    let tags: Vec<String> = app
+        .state::<RwLock<TagSubscriptionStore>>()
+        .read_or_panic()
+        .subscriptions()
+        .into_iter()
+        .map(|s| s.tag)
+        .collect();
+
+    for tag in tags {
+        let pagination = pica_client
+            .search_comic(&tag, Sort::TimeNewest, 1, vec![])
+            .await?;
+        let comic_ids: Vec<String> = pagination.docs.iter().map(|c| c.id.clone()).collect();
+        let new_ids = {
+            let mut tag_subscriptions = app.state::<RwLock<TagSubscriptionStore>>().write_or_panic();
+            let new_ids = tag_subscriptions.mark_seen(&tag, &comic_ids);
+            tag_subscriptions.save(app)?;
+            new_ids
+        };
+        if new_ids.is_empty() {
+            continue;
+        }
+
+        let auto_download_sample = app
+            .state::<RwLock<Config>>()
+            .read_or_panic()
+            .tag_subscription_auto_download_sample;
+        for comic in pagination.docs.iter().filter(|c| new_ids.contains(&c.id)) {
+            crate::events::emit_event(
+                app,
+                NewTagComicFoundEvent(NewTagComicFoundEventPayload {
+                    tag: tag.clone(),
+                    comic_id: comic.id.clone(),
+                    title: comic.title.clone(),
+                    author: comic.author.clone(),
+                }),
+            );
+            if auto_download_sample {
+                if let Err(err) = download_first_episode_sample(
+                    pica_client,
+                    download_manager,
+                    &comic.id,
+                    &comic.title,
+                    &comic.author,
+                )
+                .await
+                {
+                    eprintln!("warn: 自动下载`{}`试读章节失败: {err}", comic.title);
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// 下载`comic_id`排序最靠前的一章用于试读，供tag订阅发现新作时自动调用
+async fn download_first_episode_sample(
+    pica_client: &Arc<dyn PicaApi>,
+    download_manager: &DownloadManager,
+    comic_id: &str,
+    comic_title: &str,
+    author: &str,
+) -> anyhow::Result<()> {
+    let episode_page = pica_client.get_episode(comic_id, 1).await?;
+    let Some(first_ep) = episode_page.docs.iter().min_by_key(|ep| ep.order) else {
+        return Ok(());
+    };
+    let ep = Episode {
+        ep_id: first_ep.id.clone(),
+        ep_title: first_ep.title.clone(),
+        comic_id: comic_id.to_string(),
+        comic_title: comic_title.to_string(),
+        author: author.to_string(),
+        is_downloaded: false,
+        order: first_ep.order,
+        raw_order: first_ep.order,
+        is_locked: first_ep.is_locked,
+        target_dir: None,
+    };
+    download_manager.submit_episode(ep).await
+}
+
+#[tauri::command(async)]
+#[specta::specta]
+#[allow(clippy::needless_pass_by_value)]
+pub fn upscale_chapter(app: AppHandle, config: State<RwLock<Config>>, ep: Episode) -> CommandResult<String> {
+    ensure_not_guest_mode(&app)?;
+    let ep_dir = ep.dir_path(&app);
+    if !ep_dir.exists() {
+        return Err(anyhow!("章节`{}`尚未下载，无法超分", ep.ep_title).into());
+    }
+
+    let (program, args) = {
+        let config = config.read_or_panic();
+        let Some(program) = config.upscale_program.clone() else {
+            return Err(anyhow!("尚未在设置中配置超分工具的可执行文件路径").into());
+        };
+        (program, config.upscale_args.clone())
+    };
+
+    let Some(parent) = ep_dir.parent() else {
+        return Err(anyhow!("无法获取`{ep_dir:?}`的父目录").into());
+    };
+    let output_dir = parent.join(format!("{}-upscaled", ep.ep_title));
+    std::fs::create_dir_all(&output_dir).map_err(|e| anyhow!("创建`{output_dir:?}`失败: {e}"))?;
+
+    let mut image_paths: Vec<PathBuf> = std::fs::read_dir(&ep_dir)
+        .map_err(|e| anyhow!("读取`{ep_dir:?}`失败: {e}"))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.is_file())
+        .collect();
+    image_paths.sort();
+
+    for image_path in image_paths {
+        let Some(file_name) = image_path.file_name() else {
+            continue;
+        };
+        let output_path = output_dir.join(file_name);
+        let resolved_args: Vec<String> = args
+            .iter()
+            .map(|arg| {
+                arg.replace("{input}", &image_path.to_string_lossy())
+                    .replace("{output}", &output_path.to_string_lossy())
+            })
+            .collect();
+
+        let status = std::process::Command::new(&program)
+            .args(&resolved_args)
+            .status()
+            .map_err(|e| anyhow!("执行超分工具`{program:?}`失败: {e}"))?;
+        if !status.success() {
+            return Err(anyhow!(
+                "超分工具处理`{image_path:?}`失败，退出码: {:?}",
+                status.code()
+            )
+            .into());
+        }
+    }
+
+    Ok(output_dir.to_string_lossy().to_string())
+}
+
+#[tauri::command(async)]
+#[specta::specta]
+#[allow(clippy::needless_pass_by_value)]
+pub fn get_reading_progress(
+    reading_progress: State<RwLock<ReadingProgressStore>>,
+    ep_id: String,
+) -> CommandResult<Option<EpisodeProgress>> {
+    Ok(reading_progress.read_or_panic().get(&ep_id))
+}
+
+#[tauri::command(async)]
+#[specta::specta]
+#[allow(clippy::needless_pass_by_value)]
+pub fn set_reading_progress(
+    app: AppHandle,
+    reading_progress: State<RwLock<ReadingProgressStore>>,
+    progress: EpisodeProgress,
+) -> CommandResult<()> {
+    let mut reading_progress = reading_progress.write_or_panic();
+    reading_progress.set(progress);
+    reading_progress.save(&app)?;
+    Ok(())
+}
+
+#[tauri::command(async)]
+#[specta::specta]
+#[allow(clippy::needless_pass_by_value)]
+pub async fn start_opds_server(
+    app: AppHandle,
+    config: State<'_, RwLock<Config>>,
+    opds_handle: State<'_, OpdsHandle>,
+) -> CommandResult<()> {
+    let port = config.read_or_panic().opds_port;
+    let shutdown_tx = opds::start_server(app.clone(), port)
+        .await
+        .map_err(|e| anyhow!("启动OPDS服务端失败: {e}"))?;
+    *opds_handle.0.lock_or_panic() = Some(shutdown_tx);
+    Ok(())
+}
+
+#[tauri::command(async)]
+#[specta::specta]
+#[allow(clippy::needless_pass_by_value)]
+pub fn stop_opds_server(opds_handle: State<OpdsHandle>) -> CommandResult<()> {
+    if let Some(shutdown_tx) = opds_handle.0.lock_or_panic().take() {
+        let _ = shutdown_tx.send(());
+    }
+    Ok(())
+}
+
+#[tauri::command(async)]
+#[specta::specta]
+#[allow(clippy::needless_pass_by_value)]
+pub async fn share_comic(
+    app: AppHandle,
+    config: State<'_, RwLock<Config>>,
+    share_state: State<'_, ShareState>,
+    comic: Comic,
+    ttl_minutes: i64,
+) -> CommandResult<String> {
+    let comic_dir = create_comic_title_to_dir_map(&config)?
+        .remove(&comic.title)
+        .ok_or_else(|| anyhow!("漫画`{}`尚未下载，无法分享", comic.title))?;
+
+    let port = config.read_or_panic().share_port;
+    share::ensure_server_running(app.clone(), port)
+        .await
+        .map_err(|e| anyhow!("启动局域网分享服务失败: {e}"))?;
+
+    let token = share::generate_token();
+    let expires_at = chrono::Utc::now() + chrono::Duration::minutes(ttl_minutes);
+    share_state.sessions.lock_or_panic().insert(
+        token.clone(),
+        share::ShareSession {
+            comic_title: comic.title.clone(),
+            dir: comic_dir,
+            expires_at,
+        },
+    );
+
+    let ip = share::local_ip().map_err(|e| anyhow!("获取本机局域网IP失败: {e}"))?;
+    Ok(format!("http://{ip}:{port}/share/{token}"))
+}
+
+#[tauri::command(async)]
+#[specta::specta]
+#[allow(clippy::needless_pass_by_value)]
+pub fn stop_share_comic(share_state: State<ShareState>, token: String) -> CommandResult<()> {
+    share_state.sessions.lock_or_panic().remove(&token);
+    Ok(())
+}
+
+#[tauri::command(async)]
+#[specta::specta]
+#[allow(clippy::needless_pass_by_value)]
+pub fn get_local_comic_meta(
+    library_index: State<RwLock<LibraryIndex>>,
+    comic_title: String,
+) -> CommandResult<LocalComicMeta> {
+    Ok(library_index.read_or_panic().get(&comic_title))
+}
+
+#[tauri::command(async)]
+#[specta::specta]
+#[allow(clippy::needless_pass_by_value)]
+pub fn set_local_tags(
+    app: AppHandle,
+    library_index: State<RwLock<LibraryIndex>>,
+    comic_title: String,
+    tags: Vec<String>,
+) -> CommandResult<()> {
+    ensure_not_guest_mode(&app)?;
+    let mut library_index = library_index.write_or_panic();
+    library_index.set_tags(&comic_title, tags);
+    library_index.save(&app)?;
+    Ok(())
+}
+
+#[tauri::command(async)]
+#[specta::specta]
+#[allow(clippy::needless_pass_by_value)]
+pub fn set_rating(
+    app: AppHandle,
+    library_index: State<RwLock<LibraryIndex>>,
+    comic_title: String,
+    rating: Option<u8>,
+) -> CommandResult<()> {
+    ensure_not_guest_mode(&app)?;
+    let mut library_index = library_index.write_or_panic();
+    library_index.set_rating(&comic_title, rating);
+    library_index.save(&app)?;
+    Ok(())
+}
+
+#[tauri::command(async)]
+#[specta::specta]
+#[allow(clippy::needless_pass_by_value)]
+pub fn get_comics_by_local_tag(
+    library_index: State<RwLock<LibraryIndex>>,
+    tag: String,
+) -> CommandResult<Vec<String>> {
+    Ok(library_index.read_or_panic().filter_by_tag(&tag))
+}
+
+/// 按下载来源类别(`search`/`rank`/`favorite`等，见[`download_comic`]的`source`参数)筛选漫画标题
+#[tauri::command(async)]
+#[specta::specta]
+#[allow(clippy::needless_pass_by_value)]
+pub fn get_comics_by_source(
+    library_index: State<RwLock<LibraryIndex>>,
+    source_category: String,
+) -> CommandResult<Vec<String>> {
+    Ok(library_index.read_or_panic().filter_by_source(&source_category))
+}
+
+fn emit_export_file_skipped(app: &AppHandle, path: &std::path::Path) {
+    let payload = ExportFileSkippedEventPayload {
+        path: path.to_string_lossy().to_string(),
+    };
+    crate::events::emit_event(app, ExportFileSkippedEvent(payload));
+}
+
+fn is_dir_empty(path: &std::path::Path) -> std::io::Result<bool> {
+    Ok(std::fs::read_dir(path)?.next().is_none())
+}
+
+fn count_images(path: &std::path::Path) -> std::io::Result<u64> {
+    let mut count = 0;
+    for entry in std::fs::read_dir(path)? {
+        let entry = entry?;
+        let path = entry.path();
+        if path.is_dir() {
+            count += count_images(&path)?;
+        } else if path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| matches!(ext.to_lowercase().as_str(), "jpg" | "jpeg" | "png" | "webp"))
+        {
+            count += 1;
+        }
+    }
+    Ok(count)
+}
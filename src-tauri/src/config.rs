@@ -1,17 +1,363 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use anyhow::Context;
 use serde::{Deserialize, Serialize};
 use specta::Type;
 use tauri::{AppHandle, Manager};
 
+use crate::download_manager::DownloadFormat;
+use crate::events::{DownloadDirUnwritableEvent, DownloadDirUnwritableEventPayload};
+use crate::export::{ExportConflictPolicy, GrayscaleMode, PdfOverlayConfig};
+use crate::types::{DisallowedDownloadPolicy, Language, LibraryDir};
+
+/// `save_config`后用于唤醒各子系统的广播：限速/下载窗口等依赖`Config`的等待循环不再傻等
+/// 固定周期，而是在配置变更的瞬间就重新读取最新配置，行为统一可预测
+#[derive(Default)]
+pub struct ConfigChangeNotifier(tokio::sync::Notify);
+
+impl ConfigChangeNotifier {
+    /// 广播一次配置变更，唤醒所有当前正在等待的子系统
+    pub fn notify(&self) {
+        self.0.notify_waiters();
+    }
+
+    /// 等待下一次配置变更广播
+    pub async fn notified(&self) {
+        self.0.notified().await;
+    }
+}
+
+/// 检测`dir`是否存在且可写：创建并立即删除一个探测文件。`Err`携带用户可读的失败原因与修复建议，
+/// 供启动检测、保存配置、手动健康检查([`crate::commands::health_check`])复用同一份判断逻辑；
+/// 文案按`language`输出中文/英文，见[`crate::i18n::t`]
+pub fn check_dir_writable(dir: &Path, language: Language) -> Result<(), (String, String)> {
+    if !dir.exists() {
+        return Err((
+            crate::i18n::t(language, "download_dir_not_exist", &[("dir", &format!("{dir:?}"))]),
+            "请在设置中重新选择下载目录".to_string(),
+        ));
+    }
+    let probe_path = dir.join(".write_probe");
+    if let Err(err) = std::fs::write(&probe_path, b"") {
+        return Err((
+            crate::i18n::t(
+                language,
+                "download_dir_not_writable",
+                &[("dir", &format!("{dir:?}")), ("err", &err.to_string())],
+            ),
+            "请检查下载目录的权限，或更换一个有写入权限的目录".to_string(),
+        ));
+    }
+    let _ = std::fs::remove_file(&probe_path);
+    Ok(())
+}
+
+/// 检测`dir`的可写性，不可写时发出[`DownloadDirUnwritableEvent`]，用于启动与保存配置时
+/// 主动暴露权限/只读盘问题，而不是等到真正下载时才报一堆IO错误
+pub fn emit_if_download_dir_unwritable(app: &AppHandle, dir: &Path, language: Language) {
+    if let Err((message, suggestion)) = check_dir_writable(dir, language) {
+        crate::events::emit_event(
+            app,
+            DownloadDirUnwritableEvent(DownloadDirUnwritableEventPayload {
+                download_dir: dir.to_string_lossy().to_string(),
+                message,
+                suggestion,
+            }),
+        );
+    }
+}
+
+/// 下载间隔的随机区间(秒)，每次休眠时从`[min, max]`中随机取值，降低被识别为爬虫的概率
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct IntervalRange {
+    pub min: u64,
+    pub max: u64,
+}
+
+impl IntervalRange {
+    pub fn random_duration(&self) -> std::time::Duration {
+        use rand::Rng;
+        let (min, max) = (self.min.min(self.max), self.min.max(self.max));
+        let secs = if min == max {
+            min
+        } else {
+            rand::thread_rng().gen_range(min..=max)
+        };
+        std::time::Duration::from_secs(secs)
+    }
+}
+
+/// 允许下载的时间段，用`[start, end)`分钟数(一天内)表示，`start > end`时表示跨越午夜(如23:00-7:00)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct DownloadWindow {
+    pub start_hour: u8,
+    pub start_minute: u8,
+    pub end_hour: u8,
+    pub end_minute: u8,
+}
+
+impl DownloadWindow {
+    pub fn contains_now(&self) -> bool {
+        use chrono::Timelike;
+        let now = chrono::Local::now().time();
+        let now_minutes = now.hour() * 60 + now.minute();
+        let start = u32::from(self.start_hour) * 60 + u32::from(self.start_minute);
+        let end = u32::from(self.end_hour) * 60 + u32::from(self.end_minute);
+        if start <= end {
+            (start..end).contains(&now_minutes)
+        } else {
+            now_minutes >= start || now_minutes < end
+        }
+    }
+}
+
+/// 单条自定义文件名字符替换规则，`from`为单个原始字符，`to`为替换后的字符串
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct FilenameCharReplacement {
+    pub from: String,
+    pub to: String,
+}
+
+/// `filename_filter`在替换非法字符之后，进一步应用的可配置规则
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct FilenameFilterRules {
+    /// 自定义字符替换，在内置的非法字符替换完成后按顺序应用
+    pub custom_replacements: Vec<FilenameCharReplacement>,
+    /// 是否移除文件名中的emoji(例如内置规则会把`*`替换为`⭐`，不需要的话可以在这里移除)
+    pub remove_emoji: bool,
+    /// 是否将全角字符(包括全角空格)转换为对应的半角字符
+    pub fullwidth_to_halfwidth: bool,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Type)]
 #[serde(rename_all = "camelCase")]
 pub struct Config {
     pub token: String,
     pub download_dir: PathBuf,
-    pub episode_download_interval: u64,
+    /// 同一漫画内，下载完一章节、准备下载下一章节前的随机等待区间(秒)
+    pub episode_download_interval: IntervalRange,
+    /// 允许下载的时间段，`None`表示不限制，全天都可以下载
+    #[serde(default)]
+    pub download_window: Option<DownloadWindow>,
+    /// 遇到`allow_download=false`的漫画时的处理策略
+    #[serde(default)]
+    pub disallowed_download_policy: DisallowedDownloadPolicy,
+    /// 域名到IP的自定义映射，请求时直接用该IP连接(同时保留原域名用于TLS SNI与Host头)，
+    /// 用于在不开代理的情况下绕过被污染的DNS解析
+    #[serde(default)]
+    pub dns_overrides: std::collections::HashMap<String, String>,
+    /// 同一章节内，下载每张图片前的随机延迟区间(秒)
+    #[serde(default = "default_image_download_interval")]
+    pub image_download_interval: IntervalRange,
+    /// 切换到不同漫画时，下载完一章节、准备下载下一本漫画的章节前的随机等待区间(秒)，
+    /// 通常设置得比`episode_download_interval`更长，用于进一步降低连续请求不同漫画的频率
+    #[serde(default = "default_comic_download_interval")]
+    pub comic_download_interval: IntervalRange,
     pub download_with_author: bool,
+    #[serde(default)]
+    pub export_conflict_policy: ExportConflictPolicy,
+    /// 导出为PDF/CBZ时是否将图片转换为灰度以减小体积
+    #[serde(default)]
+    pub grayscale_mode: GrayscaleMode,
+    /// 除`download_dir`外，额外的带标签下载根目录（库分区），例如把完结漫画放NAS、连载放本地盘
+    #[serde(default)]
+    pub library_dirs: Vec<LibraryDir>,
+    /// 外部超分辨率工具的可执行文件路径，例如realesrgan-ncnn-vulkan，留空则不启用超分功能
+    #[serde(default)]
+    pub upscale_program: Option<PathBuf>,
+    /// 调用超分工具时附加的参数，`{input}`和`{output}`会被替换为实际的输入/输出图片路径
+    #[serde(default)]
+    pub upscale_args: Vec<String>,
+    /// OPDS服务端监听的端口
+    #[serde(default = "default_opds_port")]
+    pub opds_port: u16,
+    /// OPDS服务端的访问密码，留空表示不需要密码即可访问
+    #[serde(default)]
+    pub opds_password: String,
+    /// 局域网分享服务端监听的端口
+    #[serde(default = "default_share_port")]
+    pub share_port: u16,
+    /// `filename_filter`的自定义规则，用于满足不同用户对特殊字符替换的偏好
+    #[serde(default)]
+    pub filename_filter_rules: FilenameFilterRules,
+    /// 自动归档导出CBZ的目标目录，留空则不启用自动归档功能
+    #[serde(default)]
+    pub archive_dir: Option<PathBuf>,
+    /// 导出PDF时页码与章节页眉的叠加层配置
+    #[serde(default)]
+    pub pdf_overlay: PdfOverlayConfig,
+    /// 下载图片时的保存格式策略
+    #[serde(default)]
+    pub download_format: DownloadFormat,
+    /// `download_format`为`Auto`时，无损源转换为JPEG使用的质量(1-100)
+    #[serde(default = "default_auto_format_quality")]
+    pub download_auto_format_quality: u8,
+    /// 本地标签/评分索引文件(`library_index.json`)的文件名，某些文件系统/同步盘上默认文件名可能有兼容性问题，
+    /// 修改后需调用`migrate_library_index_filename`迁移旧文件
+    #[serde(default = "default_library_index_filename")]
+    pub library_index_filename: String,
+    /// 只读的访客浏览模式，开启后会修改磁盘或发起批量请求的command一律拒绝执行，
+    /// 只保留浏览类command，便于给他人演示时避免误触下载/删除
+    #[serde(default)]
+    pub guest_mode: bool,
+    /// 备用API域名，App主域名(`picaapi.picacomic.com`)被风控时用于提高可用性；
+    /// 留空表示不启用。签名算法与主域名保持一致，官方是否存在独立签名方案的Web版接口尚未确认，
+    /// 故这里只做域名级别的切换，而非单独适配一套不同的签名方式
+    #[serde(default)]
+    pub pica_backup_host: Option<String>,
+    /// 请求API时优先使用的域名通道，见[`crate::types::PicaChannel`]
+    #[serde(default)]
+    pub pica_channel: crate::types::PicaChannel,
+    /// 请求API时`app-channel`请求头使用的分流线路，见[`crate::types::ApiChannel`]
+    #[serde(default)]
+    pub api_channel: crate::types::ApiChannel,
+    /// 请求图片时`image-quality`请求头使用的画质，见[`crate::types::ImageQuality`]
+    #[serde(default)]
+    pub image_quality: crate::types::ImageQuality,
+    /// 是否合并发送整体下载进度事件，见[`crate::download_manager::DownloadManager::notify_progress_update`]；
+    /// 关闭后恢复为每张图片下载完成都立即emit一次
+    #[serde(default = "default_batch_progress_events")]
+    pub batch_progress_events: bool,
+    /// 全部下载任务完成且队列为空时自动执行的电源操作，默认关闭，
+    /// 见[`crate::download_manager::DownloadManager::maybe_trigger_auto_power_action`]
+    #[serde(default)]
+    pub auto_power_action: crate::types::AutoPowerAction,
+    /// 触发`auto_power_action`前的倒计时(秒)，期间可通过`cancel_auto_power_action`取消
+    #[serde(default = "default_auto_power_countdown_secs")]
+    pub auto_power_countdown_secs: u32,
+    /// 是否开启剪贴板监听，开启后检测到剪贴板内容为哔咔漫画id/链接时会发出
+    /// [`crate::events::ClipboardComicFoundEvent`]，默认关闭，见[`crate::clipboard_watcher`]
+    #[serde(default)]
+    pub clipboard_watcher_enabled: bool,
+    /// 是否开启跨章节去重存储：不同章节内容完全相同的图片(如重复的封面图)只保留一份物理文件，
+    /// 其余用硬链接指向该文件，默认关闭，见[`crate::content_index`]
+    #[serde(default)]
+    pub cross_episode_dedup_enabled: bool,
+    /// 界面与错误文案使用的语言，见[`crate::i18n::t`]
+    #[serde(default)]
+    pub language: crate::types::Language,
+    /// 下载漫画时是否在其目录下额外生成一份纯文本的`info.txt`(标题/作者/标签/简介/章节清单/下载时间)，
+    /// 见[`crate::commands::write_comic_info_file`]
+    #[serde(default)]
+    pub export_info_file: bool,
+    /// tag订阅调度器的检查间隔(秒)，见[`crate::commands::run_tag_subscription_scheduler`]
+    #[serde(default = "default_tag_subscription_check_interval_secs")]
+    pub tag_subscription_check_interval_secs: u64,
+    /// tag订阅发现新作时，是否自动下载该作品的第一章用于试读
+    #[serde(default)]
+    pub tag_subscription_auto_download_sample: bool,
+    /// 下载失败熔断的滑动窗口时长(秒)，配合`circuit_breaker_failure_threshold`统计近期失败率
+    #[serde(default = "default_circuit_breaker_window_secs")]
+    pub circuit_breaker_window_secs: u64,
+    /// 滑动窗口内触发熔断所需的最少失败次数(避免样本太小时个别失败就被误判为大面积失败)，
+    /// 需要与`circuit_breaker_failure_rate`同时满足才会真正熔断；设为`0`表示关闭熔断功能
+    #[serde(default = "default_circuit_breaker_failure_threshold")]
+    pub circuit_breaker_failure_threshold: u32,
+    /// 滑动窗口内失败次数达到`circuit_breaker_failure_threshold`后，还需失败率
+    /// (失败次数/(失败次数+成功次数))达到该比例(`0.0`~`1.0`)才触发全局熔断，暂停所有下载任务；
+    /// 避免大批量下载中个别失败、但整体成功率依然健康时被误判为"大量任务因同一原因失败"
+    #[serde(default = "default_circuit_breaker_failure_rate")]
+    pub circuit_breaker_failure_rate: f64,
+    /// 熔断后的冷却时间(秒)，超时后自动恢复；也可调用`resume_download_circuit_breaker`手动提前恢复
+    #[serde(default = "default_circuit_breaker_cooldown_secs")]
+    pub circuit_breaker_cooldown_secs: u64,
+    /// 每日最大下载图片数配额，达到后自动暂停剩余下载任务直到次日；`None`表示不限制
+    #[serde(default)]
+    pub daily_image_quota: Option<u32>,
+    /// 每日最大下载章节数配额，达到后自动暂停剩余下载任务直到次日；`None`表示不限制
+    #[serde(default)]
+    pub daily_episode_quota: Option<u32>,
+    /// 正在下载中的章节使用的临时目录名前缀，见[`crate::download_manager`]；
+    /// Windows上以`.`开头的目录并不会自动隐藏，因此临时目录额外会被设置隐藏属性
+    #[serde(default = "default_temp_dir_prefix")]
+    pub temp_dir_prefix: String,
+    /// 导出CBZ时是否额外写入`ComicInfo.xml`，其中`Series`/`LocalizedSeries`由标题中的括号
+    /// 原名/译名拆分而来，见[`crate::export::build_comic_info_xml`]，提升在Komga等系统中的检索体验
+    #[serde(default)]
+    pub export_comic_info_xml: bool,
+    /// 离线模式，开启后拒绝所有需要联网的command（登录、搜索、拉取漫画详情/图片等），
+    /// 只保留浏览本地已下载内容的操作，见[`crate::commands::ensure_not_offline_mode`]
+    #[serde(default)]
+    pub offline_mode: bool,
+    /// 新漫画默认保存目录的剩余空间低于该阈值(单位MB)时，自动切换到`library_dirs`中
+    /// 下一个剩余空间足够的库分区；`None`表示不启用该功能，见[`crate::commands::resolve_download_target_dir`]
+    #[serde(default)]
+    pub low_disk_space_threshold_mb: Option<u64>,
+    /// 网络请求使用的代理策略，见[`crate::types::ProxyMode`]
+    #[serde(default)]
+    pub proxy_mode: crate::types::ProxyMode,
+    /// `proxy_mode`为`Custom`时使用的代理协议，见[`crate::types::ProxyScheme`]
+    #[serde(default)]
+    pub proxy_scheme: crate::types::ProxyScheme,
+    /// `proxy_mode`为`Custom`时使用的代理主机
+    #[serde(default)]
+    pub proxy_host: Option<String>,
+    /// `proxy_mode`为`Custom`时使用的代理端口
+    #[serde(default)]
+    pub proxy_port: Option<u16>,
+    /// 点击"下载"时默认帮用户勾选哪些章节，见[`crate::types::DefaultChapterSelection`]
+    #[serde(default)]
+    pub default_chapter_selection: crate::types::DefaultChapterSelection,
+}
+
+fn default_share_port() -> u16 {
+    8339
+}
+
+fn default_image_download_interval() -> IntervalRange {
+    IntervalRange { min: 0, max: 0 }
+}
+
+fn default_comic_download_interval() -> IntervalRange {
+    IntervalRange { min: 0, max: 0 }
+}
+
+fn default_opds_port() -> u16 {
+    8338
+}
+
+fn default_auto_format_quality() -> u8 {
+    80
+}
+
+fn default_batch_progress_events() -> bool {
+    true
+}
+
+fn default_auto_power_countdown_secs() -> u32 {
+    60
+}
+
+fn default_library_index_filename() -> String {
+    "library_index.json".to_string()
+}
+
+fn default_tag_subscription_check_interval_secs() -> u64 {
+    3600
+}
+
+fn default_circuit_breaker_window_secs() -> u64 {
+    60
+}
+
+fn default_circuit_breaker_failure_threshold() -> u32 {
+    10
+}
+
+fn default_circuit_breaker_failure_rate() -> f64 {
+    0.5
+}
+
+fn default_circuit_breaker_cooldown_secs() -> u64 {
+    600
+}
+
+fn default_temp_dir_prefix() -> String {
+    ".下载中-".to_string()
 }
 
 impl Config {
@@ -21,8 +367,56 @@ impl Config {
         let default_config = Config {
             token: String::new(),
             download_dir: app.path().app_data_dir()?.join("漫画下载"),
-            episode_download_interval: 0,
+            episode_download_interval: IntervalRange { min: 0, max: 0 },
+            download_window: None,
+            disallowed_download_policy: DisallowedDownloadPolicy::default(),
+            dns_overrides: std::collections::HashMap::new(),
+            image_download_interval: default_image_download_interval(),
+            comic_download_interval: default_comic_download_interval(),
             download_with_author: false,
+            export_conflict_policy: ExportConflictPolicy::default(),
+            grayscale_mode: GrayscaleMode::default(),
+            library_dirs: vec![],
+            upscale_program: None,
+            upscale_args: vec!["{input}".to_string(), "{output}".to_string()],
+            opds_port: default_opds_port(),
+            opds_password: String::new(),
+            share_port: default_share_port(),
+            filename_filter_rules: FilenameFilterRules::default(),
+            archive_dir: None,
+            pdf_overlay: PdfOverlayConfig::default(),
+            download_format: DownloadFormat::default(),
+            download_auto_format_quality: default_auto_format_quality(),
+            library_index_filename: default_library_index_filename(),
+            guest_mode: false,
+            pica_backup_host: None,
+            pica_channel: crate::types::PicaChannel::default(),
+            api_channel: crate::types::ApiChannel::default(),
+            image_quality: crate::types::ImageQuality::default(),
+            batch_progress_events: default_batch_progress_events(),
+            auto_power_action: crate::types::AutoPowerAction::default(),
+            auto_power_countdown_secs: default_auto_power_countdown_secs(),
+            clipboard_watcher_enabled: false,
+            cross_episode_dedup_enabled: false,
+            language: crate::types::Language::default(),
+            export_info_file: false,
+            tag_subscription_check_interval_secs: default_tag_subscription_check_interval_secs(),
+            tag_subscription_auto_download_sample: false,
+            circuit_breaker_window_secs: default_circuit_breaker_window_secs(),
+            circuit_breaker_failure_threshold: default_circuit_breaker_failure_threshold(),
+            circuit_breaker_failure_rate: default_circuit_breaker_failure_rate(),
+            circuit_breaker_cooldown_secs: default_circuit_breaker_cooldown_secs(),
+            daily_image_quota: None,
+            daily_episode_quota: None,
+            temp_dir_prefix: default_temp_dir_prefix(),
+            export_comic_info_xml: false,
+            offline_mode: false,
+            low_disk_space_threshold_mb: None,
+            proxy_mode: crate::types::ProxyMode::default(),
+            proxy_scheme: crate::types::ProxyScheme::default(),
+            proxy_host: None,
+            proxy_port: None,
+            default_chapter_selection: crate::types::DefaultChapterSelection::default(),
         };
         // 如果配置文件存在且能够解析，则使用配置文件中的配置，否则使用默认配置
         let config = if config_path.exists() {
@@ -38,9 +432,20 @@ impl Config {
             ))?;
         }
         config.save(app)?;
+        emit_if_download_dir_unwritable(app, &config.download_dir, config.language);
         Ok(config)
     }
 
+    /// 返回所有库分区（`download_dir`本身加上`library_dirs`），供需要聚合多个下载根目录的command使用
+    pub fn all_library_dirs(&self) -> Vec<LibraryDir> {
+        let mut dirs = vec![LibraryDir {
+            label: "默认".to_string(),
+            dir: self.download_dir.clone(),
+        }];
+        dirs.extend(self.library_dirs.clone());
+        dirs
+    }
+
     pub fn save(&self, app: &AppHandle) -> anyhow::Result<()> {
         let resource_dir = app.path().app_data_dir()?;
         let config_path = resource_dir.join("config.json");
@@ -48,4 +453,30 @@ impl Config {
         std::fs::write(config_path, config_string)?;
         Ok(())
     }
+
+    /// 按`proxy_mode`等代理设置为`builder`应用代理
+    ///
+    /// `System`模式下reqwest已默认识别系统代理环境变量/设置，不做任何处理；`Disabled`显式关闭代理；
+    /// `Custom`按`proxy_scheme`/`proxy_host`/`proxy_port`拼出代理地址，缺少主机或端口、
+    /// 或拼出的地址无效时静默忽略，退回不使用自定义代理
+    pub fn apply_proxy(&self, builder: reqwest::ClientBuilder) -> reqwest::ClientBuilder {
+        use crate::types::{ProxyMode, ProxyScheme};
+        match self.proxy_mode {
+            ProxyMode::System => builder,
+            ProxyMode::Disabled => builder.no_proxy(),
+            ProxyMode::Custom => {
+                let (Some(host), Some(port)) = (&self.proxy_host, self.proxy_port) else {
+                    return builder;
+                };
+                let scheme = match self.proxy_scheme {
+                    ProxyScheme::Http => "http",
+                    ProxyScheme::Socks5 => "socks5",
+                };
+                match reqwest::Proxy::all(format!("{scheme}://{host}:{port}")) {
+                    Ok(proxy) => builder.proxy(proxy),
+                    Err(_) => builder,
+                }
+            }
+        }
+    }
 }
@@ -0,0 +1,66 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tauri::{AppHandle, Manager};
+
+/// 库级内容指纹索引：记录每种图片内容(按SHA-256哈希)第一次落盘的规范路径，
+/// 供`Config.cross_episode_dedup_enabled`开启时的跨章节去重存储使用，
+/// 见[`crate::download_manager::save_image_with_dedup`]
+///
+/// 不同章节出现完全相同的图片(如重复的封面)时，后出现的一份不再写入新的物理文件，
+/// 而是硬链接到索引里记录的规范路径，导出时硬链接对`std::fs::read`等文件操作完全透明，
+/// 因此CBZ/PDF/EPUB等导出逻辑无需任何改动即可展开为完整章节
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ContentIndex {
+    /// 内容哈希(hex) -> 该内容第一次写入的文件绝对路径
+    hash_to_canonical_path: HashMap<String, PathBuf>,
+}
+
+impl ContentIndex {
+    pub fn new(app: &AppHandle) -> anyhow::Result<Self> {
+        let path = Self::path(app)?;
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let string = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&string).unwrap_or_default())
+    }
+
+    pub fn save(&self, app: &AppHandle) -> anyhow::Result<()> {
+        let path = Self::path(app)?;
+        let string = serde_json::to_string_pretty(self)?;
+        std::fs::write(path, string)?;
+        Ok(())
+    }
+
+    /// 查找`data`对应内容哈希的规范路径，若该路径已不存在(如被用户手动删除)则视为未命中
+    pub fn find_canonical_path(&self, data: &[u8]) -> Option<&Path> {
+        let hash = Self::hash(data);
+        self.hash_to_canonical_path
+            .get(&hash)
+            .map(PathBuf::as_path)
+            .filter(|path| path.exists())
+    }
+
+    /// 将`data`的内容哈希登记为以`canonical_path`为规范路径
+    pub fn record(&mut self, data: &[u8], canonical_path: PathBuf) {
+        let hash = Self::hash(data);
+        self.hash_to_canonical_path.insert(hash, canonical_path);
+    }
+
+    fn hash(data: &[u8]) -> String {
+        let digest = Sha256::digest(data);
+        format!("{digest:x}")
+    }
+
+    fn path(app: &AppHandle) -> anyhow::Result<PathBuf> {
+        let app_data_dir = app
+            .path()
+            .app_data_dir()
+            .context("failed to get app data dir")?;
+        Ok(app_data_dir.join("content_index.json"))
+    }
+}
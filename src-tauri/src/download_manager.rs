@@ -1,26 +1,70 @@
-use std::path::PathBuf;
-use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+use std::collections::{HashMap, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
 use std::sync::{Arc, Mutex, RwLock};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use anyhow::{anyhow, Context};
 use bytes::Bytes;
+use chrono::Utc;
 use reqwest::StatusCode;
 use reqwest_middleware::{ClientBuilder, ClientWithMiddleware};
 use reqwest_retry::policies::ExponentialBackoff;
 use reqwest_retry::RetryTransientMiddleware;
+use serde::{Deserialize, Serialize};
+use specta::Type;
 use tauri::{AppHandle, Manager};
-use tauri_specta::Event;
 use tokio::sync::mpsc::Receiver;
 use tokio::sync::{mpsc, Semaphore};
 use tokio::task::JoinSet;
 
-use crate::config::Config;
+use crate::config::{Config, ConfigChangeNotifier};
+use crate::content_index::ContentIndex;
+use crate::download_quota::DownloadQuotaStore;
 use crate::events;
-use crate::events::{DownloadSpeedEvent, DownloadSpeedEventPayload};
+use crate::events::{
+    AutoPowerCountdownEvent, AutoPowerCountdownEventPayload, DownloadPausedEvent,
+    DownloadPausedEventPayload, DownloadSpeedEvent, DownloadSpeedEventPayload, DownloadWaitEvent,
+    DownloadWaitEventPayload,
+};
 use crate::extensions::{AnyhowErrorToStringChain, IgnoreLockPoison, IgnoreRwLockPoison};
-use crate::pica_client::PicaClient;
-use crate::types::Episode;
+use crate::jobs::JobRegistry;
+use crate::log::{FrontendLogState, LogLevel};
+use crate::pending_downloads::PendingDownloadsStore;
+use crate::pica_api::PicaApi;
+use crate::types::{
+    AutoPowerAction, ComicDownloadProgress, DebugDownloadImageResult, Episode, SpeedSample,
+};
+
+/// `img_sem`的并发许可数，`prepare_shutdown`会尝试获取同样数量的许可，
+/// 只有当前没有任何图片正在从网络下载时才能获取成功；许可在下载完成后、格式转换与写盘之前就已释放，
+/// 因此这不会等待写盘完成
+const IMG_CONCURRENCY: u32 = 40;
+
+/// `speed_history`保留的采样时长，`log_download_speed`每秒采样一次，故环形缓冲区容量为`SPEED_HISTORY_MINUTES * 60`
+const SPEED_HISTORY_MINUTES: u64 = 10;
+
+/// `Config.batch_progress_events`开启时，合并发送整体下载进度事件的周期
+const PROGRESS_EVENT_BATCH_INTERVAL: Duration = Duration::from_millis(200);
+
+/// 下载图片时的保存格式策略
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub enum DownloadFormat {
+    /// 保持源图片的原始格式，不做任何转换
+    #[default]
+    Original,
+    /// 智能模式：JPEG、GIF源保持原样；PNG/WebP等无损源转换为指定质量的JPEG以减小体积
+    /// (`image`库目前只支持无损WebP编码，暂无法输出有损WebP，故用JPEG代替)
+    Auto,
+}
+
+/// 单本漫画的聚合下载进度，key为`comic_id`
+#[derive(Debug, Clone)]
+struct ComicProgressEntry {
+    comic_title: String,
+    total_episode_count: u32,
+    completed_episode_count: u32,
+}
 
 /// 用于管理下载任务
 ///
@@ -28,11 +72,10 @@ use crate::types::Episode;
 /// 可以放心地在多个线程中传递和使用它的克隆副本。
 ///
 /// 具体来说：
-/// - `client`和`app`的克隆开销很小。
+/// - `app`的克隆开销很小。
 /// - 其他字段都被 `Arc` 包裹，这些字段的克隆操作仅仅是增加引用计数。
 #[derive(Clone)]
 pub struct DownloadManager {
-    client: ClientWithMiddleware,
     app: AppHandle,
     sender: Arc<mpsc::Sender<Episode>>,
     ep_sem: Arc<Semaphore>,
@@ -40,38 +83,309 @@ pub struct DownloadManager {
     byte_per_sec: Arc<AtomicU64>,
     downloaded_image_count: Arc<AtomicU32>,
     total_image_count: Arc<AtomicU32>,
+    comic_progress: Arc<Mutex<HashMap<String, ComicProgressEntry>>>,
+    /// 最近`SPEED_HISTORY_MINUTES`分钟的下载速度采样，供前端刷新页面后仍能画出完整的历史曲线
+    speed_history: Arc<Mutex<VecDeque<SpeedSample>>>,
+    /// 应用正在优雅停机，尚未开始下载的章节会直接暂停，保留在持久化队列中
+    shutting_down: Arc<AtomicBool>,
+    /// 滑动窗口内的下载结果时间戳(`true`为失败，`false`为成功)，用于计算失败率并触发熔断，
+    /// 见[`Self::record_download_failure`]
+    outcome_timestamps: Arc<Mutex<VecDeque<(Instant, bool)>>>,
+    /// 是否处于熔断状态：暂停所有下载任务，直到手动或超时后恢复
+    circuit_broken: Arc<AtomicBool>,
+    /// 触发熔断时的原因，供前端展示
+    circuit_break_reason: Arc<Mutex<Option<String>>>,
+    /// 手动恢复熔断时用于立即唤醒正在等待冷却的任务，而不必等到冷却结束
+    circuit_resume_notify: Arc<tokio::sync::Notify>,
+    /// 最近一个下载完成的章节所属的`comic_id`，用于判断下一个完成的章节是否属于同一本漫画，
+    /// 从而决定使用`episode_download_interval`还是`comic_download_interval`休眠
+    last_comic_id: Arc<Mutex<Option<String>>>,
+    /// `Config.batch_progress_events`开启时，标记自上次发送`UpdateOverallDownloadProgressEvent`
+    /// 以来是否有新的下载完成，由[`Self::batch_progress_event_loop`]按固定周期消费并发送，
+    /// 避免大批量任务时每张图片都单独emit一次事件导致IPC压力过大
+    progress_dirty: Arc<AtomicBool>,
+    /// 用于取消正在倒计时的`auto_power_action`，见[`Self::cancel_auto_power_action`]
+    auto_power_cancel: Arc<AtomicBool>,
 }
 
 impl DownloadManager {
     pub fn new(app: AppHandle) -> Self {
-        let retry_policy = ExponentialBackoff::builder().build_with_max_retries(2);
-        let client = ClientBuilder::new(reqwest::Client::new())
-            .with(RetryTransientMiddleware::new_with_policy(retry_policy))
-            .build();
-
         let (sender, receiver) = mpsc::channel::<Episode>(32);
         let manager = DownloadManager {
-            client,
             app,
             sender: Arc::new(sender),
             ep_sem: Arc::new(Semaphore::new(3)),
-            img_sem: Arc::new(Semaphore::new(40)),
+            img_sem: Arc::new(Semaphore::new(IMG_CONCURRENCY as usize)),
             byte_per_sec: Arc::new(AtomicU64::new(0)),
             downloaded_image_count: Arc::new(AtomicU32::new(0)),
             total_image_count: Arc::new(AtomicU32::new(0)),
+            comic_progress: Arc::new(Mutex::new(HashMap::new())),
+            speed_history: Arc::new(Mutex::new(VecDeque::new())),
+            shutting_down: Arc::new(AtomicBool::new(false)),
+            outcome_timestamps: Arc::new(Mutex::new(VecDeque::new())),
+            circuit_broken: Arc::new(AtomicBool::new(false)),
+            circuit_break_reason: Arc::new(Mutex::new(None)),
+            circuit_resume_notify: Arc::new(tokio::sync::Notify::new()),
+            last_comic_id: Arc::new(Mutex::new(None)),
+            progress_dirty: Arc::new(AtomicBool::new(false)),
+            auto_power_cancel: Arc::new(AtomicBool::new(false)),
         };
 
         // TODO: 改用tauri::async_runtime::spawn
         tokio::spawn(manager.clone().log_download_speed());
         tokio::spawn(manager.clone().receiver_loop(receiver));
+        tokio::spawn(manager.clone().batch_progress_event_loop());
 
         manager
     }
 
+    /// 按当前最新的代理设置构建一个带重试中间件的图片下载client；每次下载图片时都重新构建，
+    /// 而不是复用一个长期持有的client，这样`save_config`更改代理设置后无需重启下载任务即可生效，
+    /// 与[`crate::pica_client::PicaClient::client`]的做法保持一致
+    fn img_client(&self) -> ClientWithMiddleware {
+        let retry_policy = ExponentialBackoff::builder().build_with_max_retries(2);
+        let config = self.app.state::<RwLock<Config>>().read_or_panic();
+        let builder = config.apply_proxy(reqwest::ClientBuilder::new());
+        drop(config);
+        let client = builder.build().unwrap();
+        ClientBuilder::new(client)
+            .with(RetryTransientMiddleware::new_with_policy(retry_policy))
+            .build()
+    }
+
     pub async fn submit_episode(&self, ep: Episode) -> anyhow::Result<()> {
+        self.register_episode(&ep);
+        {
+            let pending = self.app.state::<RwLock<PendingDownloadsStore>>();
+            let mut pending = pending.write_or_panic();
+            pending.add(ep.clone());
+            pending.save(&self.app)?;
+        }
         Ok(self.sender.send(ep).await?)
     }
 
+    /// 应用退出前调用：暂停所有尚未开始下载的章节(会保留在持久化队列中，下次启动时自动恢复)，
+    /// 并等待当前正在从网络下载的图片全部下载完毕；下载完毕后的格式转换与写盘不在等待范围内
+    /// (写盘先写临时文件再rename，正式路径处不会留下半截文件)
+    pub async fn prepare_shutdown(&self) {
+        self.shutting_down.store(true, Ordering::Relaxed);
+        let _ = self.img_sem.acquire_many(IMG_CONCURRENCY).await;
+    }
+
+    /// 将章节计入其所属漫画的聚合进度(总章节数+1)，并广播最新进度
+    fn register_episode(&self, ep: &Episode) {
+        let progress = {
+            let mut comic_progress = self.comic_progress.lock_or_panic();
+            let entry = comic_progress
+                .entry(ep.comic_id.clone())
+                .or_insert_with(|| ComicProgressEntry {
+                    comic_title: ep.comic_title.clone(),
+                    total_episode_count: 0,
+                    completed_episode_count: 0,
+                });
+            entry.total_episode_count += 1;
+            entry.clone()
+        };
+        emit_comic_download_progress_event(&self.app, &ep.comic_id, &progress);
+    }
+
+    /// 标记该章节已结束下载(无论成功或失败)，并广播最新的聚合进度
+    ///
+    /// 当该漫画的所有章节都已结束下载后，清空其聚合进度记录
+    fn complete_episode(&self, comic_id: &str) {
+        let progress = {
+            let mut comic_progress = self.comic_progress.lock_or_panic();
+            let Some(entry) = comic_progress.get_mut(comic_id) else {
+                return;
+            };
+            entry.completed_episode_count += 1;
+            let progress = entry.clone();
+            if entry.completed_episode_count >= entry.total_episode_count {
+                comic_progress.remove(comic_id);
+            }
+            progress
+        };
+        emit_comic_download_progress_event(&self.app, comic_id, &progress);
+    }
+
+    /// 获取指定漫画当前的聚合下载进度，如果该漫画没有正在进行的下载任务则返回`None`
+    pub fn get_comic_download_progress(&self, comic_id: &str) -> Option<ComicDownloadProgress> {
+        let comic_progress = self.comic_progress.lock_or_panic();
+        let entry = comic_progress.get(comic_id)?;
+        Some(to_comic_download_progress(comic_id, entry))
+    }
+
+    /// 如果配置了允许下载的时间段且当前不在其中，则持续等待直到进入该时间段
+    async fn wait_for_download_window(&self) {
+        let mut paused = false;
+        loop {
+            let download_window = self
+                .app
+                .state::<RwLock<Config>>()
+                .read_or_panic()
+                .download_window;
+            let Some(download_window) = download_window else {
+                return;
+            };
+            if download_window.contains_now() {
+                return;
+            }
+            if !paused {
+                emit_download_paused_event(&self.app, "不在允许下载的时间段内".to_string());
+                paused = true;
+            }
+            // 配置变更(如调整/关闭下载窗口)时立即被唤醒重新检查，而不是傻等满60秒
+            let config_changed = self.app.state::<ConfigChangeNotifier>().notified();
+            tokio::select! {
+                () = tokio::time::sleep(Duration::from_secs(60)) => {}
+                () = config_changed => {}
+            }
+        }
+    }
+
+    /// 当日下载量达到`Config.daily_image_quota`/`daily_episode_quota`配额时，暂停所有下载任务直到次日配额重置
+    async fn wait_for_daily_quota(&self) {
+        let mut paused = false;
+        loop {
+            let (image_quota, episode_quota) = {
+                let config = self.app.state::<RwLock<Config>>().read_or_panic();
+                (config.daily_image_quota, config.daily_episode_quota)
+            };
+            let (image_count, episode_count) = {
+                let mut quota = self.app.state::<RwLock<DownloadQuotaStore>>().write_or_panic();
+                (quota.image_count(), quota.episode_count())
+            };
+            let quota_exceeded = image_quota.is_some_and(|limit| image_count >= limit)
+                || episode_quota.is_some_and(|limit| episode_count >= limit);
+            if !quota_exceeded {
+                return;
+            }
+            if !paused {
+                emit_download_paused_event(&self.app, "今日下载配额已用完，将于次日自动恢复".to_string());
+                paused = true;
+            }
+            // 配置变更(如调高/关闭配额)时立即被唤醒重新检查，而不是傻等到下一次轮询
+            let config_changed = self.app.state::<ConfigChangeNotifier>().notified();
+            tokio::select! {
+                () = tokio::time::sleep(Duration::from_secs(60)) => {}
+                () = config_changed => {}
+            }
+        }
+    }
+
+    /// 记录一次已下载的图片，计入当日下载量配额
+    fn record_downloaded_image(&self) {
+        let mut quota = self.app.state::<RwLock<DownloadQuotaStore>>().write_or_panic();
+        quota.record_image();
+        let _ = quota.save(&self.app);
+    }
+
+    /// 记录一次下载成功的章节，计入当日下载量配额
+    fn record_downloaded_episode(&self) {
+        let mut quota = self.app.state::<RwLock<DownloadQuotaStore>>().write_or_panic();
+        quota.record_episode();
+        let _ = quota.save(&self.app);
+    }
+
+    /// 记录一次下载成功，计入滑动窗口，用于在[`Self::record_download_failure`]中计算近期失败率
+    fn record_download_success(&self) {
+        if self.circuit_broken.load(Ordering::Relaxed) {
+            return;
+        }
+        let window_secs = self
+            .app
+            .state::<RwLock<Config>>()
+            .read_or_panic()
+            .circuit_breaker_window_secs;
+        let now = Instant::now();
+        let mut outcomes = self.outcome_timestamps.lock_or_panic();
+        outcomes.push_back((now, false));
+        prune_outcome_window(&mut outcomes, now, window_secs);
+    }
+
+    /// 记录一次下载失败，滑动窗口内的失败次数达到`Config.circuit_breaker_failure_threshold`、
+    /// 且失败率达到`Config.circuit_breaker_failure_rate`时触发全局熔断，暂停所有下载任务，
+    /// 避免因token失效/IP被封一类的原因连环失败；只看绝对失败次数会在大批量下载中把个别偶发失败
+    /// 误判为大面积失败，因此必须同时满足失败率才熔断
+    fn record_download_failure(&self, reason: &str) {
+        if self.circuit_broken.load(Ordering::Relaxed) {
+            return;
+        }
+        let (window_secs, min_failures, failure_rate_threshold) = {
+            let config = self.app.state::<RwLock<Config>>().read_or_panic();
+            (
+                config.circuit_breaker_window_secs,
+                config.circuit_breaker_failure_threshold,
+                config.circuit_breaker_failure_rate,
+            )
+        };
+        // 阈值为0表示关闭熔断功能
+        if min_failures == 0 {
+            return;
+        }
+        let now = Instant::now();
+        let tripped = {
+            let mut outcomes = self.outcome_timestamps.lock_or_panic();
+            outcomes.push_back((now, true));
+            prune_outcome_window(&mut outcomes, now, window_secs);
+            let failure_count = outcomes.iter().filter(|(_, is_failure)| *is_failure).count() as u32;
+            let total = outcomes.len() as u32;
+            let failure_rate = f64::from(failure_count) / f64::from(total);
+            if failure_count >= min_failures && failure_rate >= failure_rate_threshold {
+                outcomes.clear();
+                true
+            } else {
+                false
+            }
+        };
+        if tripped {
+            self.trip_circuit_breaker(reason.to_string());
+        }
+    }
+
+    fn trip_circuit_breaker(&self, reason: String) {
+        self.circuit_broken.store(true, Ordering::Relaxed);
+        *self.circuit_break_reason.lock_or_panic() = Some(reason.clone());
+        emit_download_paused_event(
+            &self.app,
+            format!("短时间内大量下载任务失败，已自动熔断暂停所有下载: {reason}"),
+        );
+    }
+
+    /// 手动恢复熔断，立即唤醒所有正在等待冷却的下载任务
+    pub fn resume_circuit_breaker(&self) {
+        self.circuit_broken.store(false, Ordering::Relaxed);
+        *self.circuit_break_reason.lock_or_panic() = None;
+        self.outcome_timestamps.lock_or_panic().clear();
+        self.circuit_resume_notify.notify_waiters();
+    }
+
+    /// 当前是否处于熔断状态，以及熔断原因(未熔断时为`None`)
+    pub fn circuit_breaker_status(&self) -> (bool, Option<String>) {
+        (
+            self.circuit_broken.load(Ordering::Relaxed),
+            self.circuit_break_reason.lock_or_panic().clone(),
+        )
+    }
+
+    /// 熔断期间暂停所有下载任务，直到手动恢复([`Self::resume_circuit_breaker`])或冷却超时后自动恢复
+    async fn wait_for_circuit_breaker(&self) {
+        while self.circuit_broken.load(Ordering::Relaxed) {
+            let cooldown_secs = self
+                .app
+                .state::<RwLock<Config>>()
+                .read_or_panic()
+                .circuit_breaker_cooldown_secs;
+            let resumed = self.circuit_resume_notify.notified();
+            tokio::select! {
+                () = tokio::time::sleep(Duration::from_secs(cooldown_secs)) => {
+                    self.resume_circuit_breaker();
+                }
+                () = resumed => {}
+            }
+        }
+    }
+
     #[allow(clippy::cast_precision_loss)]
     async fn log_download_speed(self) {
         let mut interval = tokio::time::interval(Duration::from_secs(1));
@@ -79,12 +393,60 @@ impl DownloadManager {
         loop {
             interval.tick().await;
             let byte_per_sec = self.byte_per_sec.swap(0, Ordering::Relaxed);
+            self.record_speed_sample(byte_per_sec);
             let mega_byte_per_sec = byte_per_sec as f64 / 1024.0 / 1024.0;
             let speed = format!("{mega_byte_per_sec:.2}MB/s");
             emit_download_speed_event(&self.app, speed);
         }
     }
 
+    /// 将本次采样追加到`speed_history`环形缓冲区，并丢弃超出`SPEED_HISTORY_MINUTES`的旧采样
+    fn record_speed_sample(&self, byte_per_sec: u64) {
+        let mut speed_history = self.speed_history.lock_or_panic();
+        speed_history.push_back(SpeedSample {
+            timestamp: Utc::now().timestamp(),
+            byte_per_sec,
+        });
+        while speed_history.len() as u64 > SPEED_HISTORY_MINUTES * 60 {
+            speed_history.pop_front();
+        }
+    }
+
+    /// 获取最近`SPEED_HISTORY_MINUTES`分钟的下载速度采样，按时间从早到晚排列
+    pub fn get_speed_history(&self) -> Vec<SpeedSample> {
+        self.speed_history.lock_or_panic().iter().cloned().collect()
+    }
+
+    /// 一张图片下载完成后更新整体下载进度：`Config.batch_progress_events`关闭时立即emit，
+    /// 开启时只标记脏位，由[`Self::batch_progress_event_loop`]按固定周期合并发送
+    fn notify_progress_update(&self, downloaded_image_count: u32, total_image_count: u32) {
+        let batch_progress_events = self
+            .app
+            .state::<RwLock<Config>>()
+            .read_or_panic()
+            .batch_progress_events;
+        if batch_progress_events {
+            self.progress_dirty.store(true, Ordering::Relaxed);
+        } else {
+            emit_update_overall_progress_event(&self.app, downloaded_image_count, total_image_count);
+        }
+    }
+
+    /// 按[`PROGRESS_EVENT_BATCH_INTERVAL`]固定周期检查脏位，有新进度才发送一次
+    /// `UpdateOverallDownloadProgressEvent`，避免大批量任务时每张图片都单独emit一次事件
+    async fn batch_progress_event_loop(self) {
+        let mut interval = tokio::time::interval(PROGRESS_EVENT_BATCH_INTERVAL);
+        loop {
+            interval.tick().await;
+            if !self.progress_dirty.swap(false, Ordering::Relaxed) {
+                continue;
+            }
+            let downloaded_image_count = self.downloaded_image_count.load(Ordering::Relaxed);
+            let total_image_count = self.total_image_count.load(Ordering::Relaxed);
+            emit_update_overall_progress_event(&self.app, downloaded_image_count, total_image_count);
+        }
+    }
+
     async fn receiver_loop(self, mut receiver: Receiver<Episode>) {
         while let Some(ep) = receiver.recv().await {
             let manager = self.clone();
@@ -92,13 +454,13 @@ impl DownloadManager {
         }
     }
 
-    #[allow(clippy::cast_possible_truncation)]
-    #[allow(clippy::too_many_lines)]
-    // TODO: 重构这个函数，减少行数
-    async fn process_episode(self, ep: Episode) {
-        emit_pending_event(&self.app, ep.ep_id.clone(), ep.ep_title.clone());
-
-        let pica_client = self.app.state::<PicaClient>().inner().clone();
+    /// 拉取该章节所有页的图片，返回`(图片下载链接列表, 服务端第一页声明的图片总数total)`；
+    /// 其中任意一页获取失败都会直接调用[`Self::finish_episode`]上报错误并返回`None`
+    async fn fetch_episode_image_urls(
+        &self,
+        ep: &Episode,
+        pica_client: &Arc<dyn PicaApi>,
+    ) -> Option<(Vec<String>, i64)> {
         // TODO: 用parking_lot::Mutex替换std::Mutex
         let images = Arc::new(Mutex::new(vec![]));
         // 先获取该章节的第一页图片
@@ -114,10 +476,11 @@ impl DownloadManager {
                 let err = err.context(format!(
                     "获取`{comic_title}`第`{ep_order}`章节`{ep_title}`的第`1`页图片失败"
                 ));
-                emit_end_event(&self.app, ep.ep_id.clone(), Some(err.to_string_chain()));
-                return;
+                self.finish_episode(ep.ep_id.clone(), &ep.comic_id, Some(err.to_string_chain()));
+                return None;
             }
         };
+        let expected_total = first_page.total;
         images.lock_or_panic().push((1, first_page.docs));
         // 根据第一页返回的总页数，创建获取剩下页数图片的任务
         let total_pages = first_page.pages;
@@ -128,10 +491,8 @@ impl DownloadManager {
             let images = images.clone();
             let comic_id = ep.comic_id.clone();
             let comic_title = ep.comic_title.clone();
-            let ep_id = ep.ep_id.clone();
             let ep_title = ep.ep_title.clone();
             let ep_order = ep.order;
-            let app = self.app.clone();
             join_set.spawn(async move {
                 let image_page = match pica_client
                     .get_episode_image(&comic_id, ep_order, page)
@@ -142,16 +503,19 @@ impl DownloadManager {
                         let err = err.context(format!(
                             "获取`{comic_title}`第`{ep_order}`章`{ep_title}`的第`{page}`页图片失败"
                         ));
-                        emit_end_event(&app, ep_id, Some(err.to_string_chain()));
-                        return;
+                        return Some(err.to_string_chain());
                     }
                 };
 
                 images.lock_or_panic().push((page, image_page.docs));
+                None
             });
         }
-        // 等待所有获取图片的任务完成
-        join_set.join_all().await;
+        // 等待所有获取图片的任务完成，任意一页失败则只上报第一个错误
+        if let Some(err_msg) = join_set.join_all().await.into_iter().flatten().next() {
+            self.finish_episode(ep.ep_id.clone(), &ep.comic_id, Some(err_msg));
+            return None;
+        }
         let mut images = std::mem::take(&mut *images.lock_or_panic());
         images.sort_by_key(|(page, _)| *page);
         // 构造图片下载链接
@@ -161,18 +525,75 @@ impl DownloadManager {
             .map(|image| (image.media.file_server, image.media.path))
             .map(|(file_server, path)| format!("{file_server}/static/{path}"))
             .collect();
+        Some((urls, expected_total))
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    #[allow(clippy::too_many_lines)]
+    // TODO: 重构这个函数，减少行数
+    async fn process_episode(self, ep: Episode) {
+        emit_pending_event(&self.app, ep.ep_id.clone(), ep.ep_title.clone());
+        // 以`ep_id`为`job_id`注册取消令牌，可通过`cancel_job`取消此章节尚未开始的下载步骤
+        let cancel_flag = self.app.state::<JobRegistry>().register(&ep.ep_id);
+        // 用于计算该章节从开始下载到现在的平均下载速度
+        let ep_start = std::time::Instant::now();
+        let ep_bytes = Arc::new(AtomicU64::new(0));
+
+        let pica_client = self.app.state::<Arc<dyn PicaApi>>().inner().clone();
+        let Some((mut urls, expected_total)) =
+            self.fetch_episode_image_urls(&ep, &pica_client).await
+        else {
+            return;
+        };
+        // 偶尔分页接口返回的docs汇总数量与声明的total不一致(缺页)，先重试一次完整拉取
+        if urls.len() as i64 != expected_total {
+            let Some((retry_urls, retry_total)) =
+                self.fetch_episode_image_urls(&ep, &pica_client).await
+            else {
+                return;
+            };
+            urls = retry_urls;
+            if urls.len() as i64 != retry_total {
+                let comic_title = &ep.comic_title;
+                let ep_title = &ep.ep_title;
+                let actual = urls.len();
+                crate::log::log_event(
+                    &self.app,
+                    &self.app.state::<FrontendLogState>(),
+                    LogLevel::Warn,
+                    format!(
+                        "`{comic_title}`章节`{ep_title}`的图片数量校验未通过(重试后仍不一致): 实际拿到`{actual}`张，服务器声明`{retry_total}`张，可能缺页"
+                    ),
+                );
+            }
+        }
 
         let total = urls.len() as u32;
         // 记录总共需要下载的图片数量
         self.total_image_count.fetch_add(total, Ordering::Relaxed);
         let downloaded_count = Arc::new(AtomicU32::new(0));
         let mut join_set = JoinSet::new();
+        // 不在允许下载的时间段内时，暂停发放permit，直到进入允许的时间段
+        self.wait_for_download_window().await;
+        // 处于熔断状态时，暂停发放permit，直到手动恢复或冷却超时
+        self.wait_for_circuit_breaker().await;
+        // 当日下载量配额已用完时，暂停发放permit，直到次日配额重置
+        self.wait_for_daily_quota().await;
+        if cancel_flag.load(Ordering::Relaxed) {
+            self.finish_episode(ep.ep_id.clone(), &ep.comic_id, Some("下载已被取消".to_string()));
+            return;
+        }
+        if self.shutting_down.load(Ordering::Relaxed) {
+            // 应用正在退出，不调用`finish_episode`，使该章节保留在持久化队列中，下次启动时恢复下载
+            self.app.state::<JobRegistry>().finish(&ep.ep_id);
+            return;
+        }
         // 限制同时下载的章节数量
         let permit = match self.ep_sem.acquire().await.map_err(anyhow::Error::from) {
             Ok(permit) => permit,
             Err(err) => {
                 let err = err.context("获取下载章节的semaphore失败");
-                emit_end_event(&self.app, ep.ep_id.clone(), Some(err.to_string_chain()));
+                self.finish_episode(ep.ep_id.clone(), &ep.comic_id, Some(err.to_string_chain()));
                 return;
             }
         };
@@ -180,18 +601,33 @@ impl DownloadManager {
         let temp_download_dir = get_temp_download_dir(&self.app, &ep);
         if let Err(err) = std::fs::create_dir_all(&temp_download_dir).map_err(anyhow::Error::from) {
             let err = err.context(format!("创建目录`{temp_download_dir:?}`失败"));
-            emit_end_event(&self.app, ep.ep_id.clone(), Some(err.to_string_chain()));
+            self.finish_episode(ep.ep_id.clone(), &ep.comic_id, Some(err.to_string_chain()));
             return;
         };
+        #[cfg(target_os = "windows")]
+        hide_temp_download_dir(&temp_download_dir);
         emit_start_event(&self.app, ep.ep_id.clone(), ep.ep_title.clone(), total);
         for (i, url) in urls.iter().enumerate() {
+            // 尚未开始下载的图片可以被取消，已派发给线程池的图片仍会下载完成
+            if cancel_flag.load(Ordering::Relaxed) {
+                break;
+            }
             let manager = self.clone();
             let ep_id = ep.ep_id.clone();
-            let save_path = temp_download_dir.join(format!("{:03}.jpg", i + 1));
+            // 最终的文件扩展名要等下载完成后才能确定(取决于下载格式策略)，这里先只确定不带扩展名的路径
+            let save_stem = temp_download_dir.join(format!("{:03}", i + 1));
             let url = url.clone();
             let downloaded_count = downloaded_count.clone();
+            let ep_bytes = ep_bytes.clone();
             // 创建下载任务
-            join_set.spawn(manager.download_image(url, save_path, ep_id, downloaded_count));
+            join_set.spawn(manager.download_image(
+                url,
+                save_stem,
+                ep_id,
+                downloaded_count,
+                ep_bytes,
+                ep_start,
+            ));
         }
         // 逐一处理完成的下载任务
         while let Some(Ok(())) = join_set.join_next().await {
@@ -199,19 +635,27 @@ impl DownloadManager {
             let downloaded_image_count = self.downloaded_image_count.load(Ordering::Relaxed);
             let total_image_count = self.total_image_count.load(Ordering::Relaxed);
             // 更新下载进度
-            emit_update_overall_progress_event(
-                &self.app,
-                downloaded_image_count,
-                total_image_count,
-            );
+            self.notify_progress_update(downloaded_image_count, total_image_count);
         }
-        let download_interval = self
-            .app
-            .state::<RwLock<Config>>()
-            .read_or_panic()
-            .episode_download_interval;
-        // 等待一段时间再下载下一章节
-        tokio::time::sleep(Duration::from_secs(download_interval)).await;
+        // 同一漫画内的下一章节沿用`episode_download_interval`，切换到不同漫画则改用更长的`comic_download_interval`
+        let same_comic = {
+            let mut last_comic_id = self.last_comic_id.lock_or_panic();
+            let same_comic = last_comic_id.as_deref() == Some(ep.comic_id.as_str());
+            *last_comic_id = Some(ep.comic_id.clone());
+            same_comic
+        };
+        let (download_interval, wait_kind) = {
+            let config = self.app.state::<RwLock<Config>>().read_or_panic();
+            if same_comic {
+                (config.episode_download_interval, "episode")
+            } else {
+                (config.comic_download_interval, "comic")
+            }
+        };
+        // 等待一段随机时间再下载下一章节
+        let wait_duration = download_interval.random_duration();
+        emit_wait_event(&self.app, ep.ep_id.clone(), wait_kind, wait_duration);
+        tokio::time::sleep(wait_duration).await;
         drop(permit);
         // 如果DownloadManager所有图片全部都已下载(无论成功或失败)，则清空下载进度
         let downloaded_image_count = self.downloaded_image_count.load(Ordering::Relaxed);
@@ -222,6 +666,10 @@ impl DownloadManager {
         }
         // 检查此章节的图片是否全部下载成功
         let downloaded_count = downloaded_count.load(Ordering::Relaxed);
+        if cancel_flag.load(Ordering::Relaxed) {
+            self.finish_episode(ep.ep_id.clone(), &ep.comic_id, Some("下载已被取消".to_string()));
+            return;
+        }
         if downloaded_count != total {
             // 此章节的图片未全部下载成功
             let comic_title = &ep.comic_title;
@@ -229,7 +677,7 @@ impl DownloadManager {
             let err_msg = Some(format!(
                 "`{comic_title}`的`{ep_title}`章节总共有`{total}`张图片，但只下载了`{downloaded_count}`张"
             ));
-            emit_end_event(&self.app, ep.ep_id.clone(), err_msg);
+            self.finish_episode(ep.ep_id.clone(), &ep.comic_id, err_msg);
             return;
         }
         // 此章节的图片全部下载成功
@@ -237,7 +685,61 @@ impl DownloadManager {
             Ok(()) => None,
             Err(err) => Some(err.to_string_chain()),
         };
-        emit_end_event(&self.app, ep.ep_id.clone(), err_msg);
+        if err_msg.is_none() {
+            self.record_downloaded_episode();
+        }
+        self.finish_episode(ep.ep_id.clone(), &ep.comic_id, err_msg);
+    }
+
+    /// 广播章节下载结束事件，并将该章节计入其所属漫画的聚合进度(已完成章节数+1)
+    fn finish_episode(&self, ep_id: String, comic_id: &str, err_msg: Option<String>) {
+        self.app.state::<JobRegistry>().finish(&ep_id);
+        {
+            let pending = self.app.state::<RwLock<PendingDownloadsStore>>();
+            let mut pending = pending.write_or_panic();
+            pending.remove(&ep_id);
+            let _ = pending.save(&self.app);
+        }
+        emit_end_event(&self.app, ep_id, err_msg);
+        self.complete_episode(comic_id);
+        if self.comic_progress.lock_or_panic().is_empty() {
+            tokio::spawn(self.clone().maybe_trigger_auto_power_action());
+        }
+    }
+
+    /// 取消正在倒计时的`auto_power_action`
+    pub fn cancel_auto_power_action(&self) {
+        self.auto_power_cancel.store(true, Ordering::Relaxed);
+    }
+
+    /// 下载队列清空后，如果配置了`auto_power_action`，则广播倒计时事件，
+    /// 倒计时期间可通过[`Self::cancel_auto_power_action`]取消，
+    /// 或因队列又有新任务加入(`comic_progress`重新非空)而自动取消
+    async fn maybe_trigger_auto_power_action(self) {
+        let (action, countdown_secs) = {
+            let config = self.app.state::<RwLock<Config>>().read_or_panic();
+            (config.auto_power_action, config.auto_power_countdown_secs)
+        };
+        if action == AutoPowerAction::Off {
+            return;
+        }
+        self.auto_power_cancel.store(false, Ordering::Relaxed);
+
+        for seconds_remaining in (0..=countdown_secs).rev() {
+            let cancelled = self.auto_power_cancel.load(Ordering::Relaxed)
+                || !self.comic_progress.lock_or_panic().is_empty();
+            if cancelled {
+                emit_auto_power_countdown_event(&self.app, action, seconds_remaining, true);
+                return;
+            }
+            emit_auto_power_countdown_event(&self.app, action, seconds_remaining, false);
+            if seconds_remaining == 0 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_secs(1)).await;
+        }
+
+        execute_power_action(action);
     }
 
     fn save_archive(&self, ep: &Episode, temp_download_dir: &PathBuf) -> anyhow::Result<()> {
@@ -259,49 +761,120 @@ impl DownloadManager {
         Ok(())
     }
 
+    #[allow(clippy::cast_precision_loss)]
     async fn download_image(
         self,
         url: String,
-        save_path: PathBuf,
+        save_stem: PathBuf,
         ep_id: String,
         downloaded_count: Arc<AtomicU32>,
+        ep_bytes: Arc<AtomicU64>,
+        ep_start: Instant,
     ) {
+        // 断点续传：如果`save_stem`处已存在任意已知扩展名的完整有效图片，说明是上次中断前已下载好的，直接跳过
+        if let Some(existing_path) = find_valid_existing_image(&save_stem) {
+            let downloaded_count = downloaded_count.fetch_add(1, Ordering::Relaxed) + 1;
+            let existing_path = existing_path.to_string_lossy().to_string();
+            emit_success_event(&self.app, ep_id, existing_path, downloaded_count, 0.0);
+            return;
+        }
         // 下载图片
         let permit = match self.img_sem.acquire().await.map_err(anyhow::Error::from) {
             Ok(permit) => permit,
             Err(err) => {
                 let err = err.context("获取下载图片的semaphore失败");
+                self.record_download_failure(&err.to_string_chain());
                 emit_error_event(&self.app, ep_id, url, err.to_string_chain());
                 return;
             }
         };
+        // 下载前随机等待一段时间，降低被识别为爬虫的概率
+        let image_download_interval = self
+            .app
+            .state::<RwLock<Config>>()
+            .read_or_panic()
+            .image_download_interval;
+        let wait_duration = image_download_interval.random_duration();
+        emit_wait_event(&self.app, ep_id.clone(), "image", wait_duration);
+        tokio::time::sleep(wait_duration).await;
+
         let image_data = match self.get_image_bytes(&url).await {
             Ok(data) => data,
             Err(err) => {
                 let err = err.context(format!("下载图片`{url}`失败"));
+                self.record_download_failure(&err.to_string_chain());
                 emit_error_event(&self.app, ep_id, url, err.to_string_chain());
                 return;
             }
         };
         drop(permit);
-        // 保存图片
-        if let Err(err) = std::fs::write(&save_path, &image_data).map_err(anyhow::Error::from) {
+        // 根据下载格式策略，决定最终要保存的字节与文件扩展名
+        let (download_format, auto_format_quality) = {
+            let config = self.app.state::<RwLock<Config>>().read_or_panic();
+            (config.download_format, config.download_auto_format_quality)
+        };
+        let (save_data, extension) =
+            match resolve_download_format(&image_data, download_format, auto_format_quality) {
+                Ok(resolved) => resolved,
+                Err(err) if download_format != DownloadFormat::Original => {
+                    // 转码失败(如遇到无法解码的畸形图片)时，自动降级为`Original`格式重试保存，
+                    // 避免整章因个别图片转码失败而卡死；`Original`模式本身不做转换，不会再失败
+                    crate::log::log_event(
+                        &self.app,
+                        &self.app.state::<FrontendLogState>(),
+                        LogLevel::Warn,
+                        format!(
+                            "转换图片`{url}`的格式失败，已自动降级为`Original`格式重试: {}",
+                            err.to_string_chain()
+                        ),
+                    );
+                    match resolve_download_format(&image_data, DownloadFormat::Original, auto_format_quality) {
+                        Ok(resolved) => resolved,
+                        Err(err) => {
+                            let err = err.context(format!("降级为`Original`格式保存图片`{url}`仍然失败"));
+                            self.record_download_failure(&err.to_string_chain());
+                            emit_error_event(&self.app, ep_id, url, err.to_string_chain());
+                            return;
+                        }
+                    }
+                }
+                Err(err) => {
+                    let err = err.context(format!("转换图片`{url}`的格式失败"));
+                    self.record_download_failure(&err.to_string_chain());
+                    emit_error_event(&self.app, ep_id, url, err.to_string_chain());
+                    return;
+                }
+            };
+        let save_path = save_stem.with_extension(extension);
+        // 保存图片：先写入临时文件再rename，避免进程崩溃时在`save_path`处留下不完整的半截文件；
+        // `Config.cross_episode_dedup_enabled`开启时，内容与库中已有图片相同则改为硬链接，节省空间
+        if let Err(err) = save_image_with_dedup(&self.app, &save_path, &save_data) {
             let err = err.context(format!("保存图片`{save_path:?}`失败"));
+            self.record_download_failure(&err.to_string_chain());
             emit_error_event(&self.app, ep_id, url, err.to_string_chain());
             return;
         }
-        // 记录下载字节数
+        // 读取并应用EXIF Orientation，统一输出正向图片，失败也不影响本次下载
+        let _ = crate::utils::correct_exif_orientation(&save_path);
+        // 记录下载字节数(按网络实际下载的字节数计算速度，而非转换后的体积)
         self.byte_per_sec
             .fetch_add(image_data.len() as u64, Ordering::Relaxed);
+        let total_ep_bytes =
+            ep_bytes.fetch_add(image_data.len() as u64, Ordering::Relaxed) + image_data.len() as u64;
+        // 计算该章节从开始下载到现在的平均下载速度
+        let elapsed_secs = ep_start.elapsed().as_secs_f64().max(0.001);
+        let bytes_per_sec = total_ep_bytes as f64 / elapsed_secs;
         // 更新章节下载进度
         let downloaded_count = downloaded_count.fetch_add(1, Ordering::Relaxed) + 1;
+        self.record_downloaded_image();
+        self.record_download_success();
         let save_path = save_path.to_string_lossy().to_string();
-        emit_success_event(&self.app, ep_id, save_path, downloaded_count);
+        emit_success_event(&self.app, ep_id, save_path, downloaded_count, bytes_per_sec);
     }
 
     // TODO: 将发送获取图片请求的逻辑移到PicaClient中
     async fn get_image_bytes(&self, url: &str) -> anyhow::Result<Bytes> {
-        let http_res = self.client.get(url).send().await?;
+        let http_res = self.img_client().get(url).send().await?;
 
         let status = http_res.status();
         if status != StatusCode::OK {
@@ -314,26 +887,192 @@ impl DownloadManager {
 
         Ok(image_data)
     }
+
+    /// 用与正式下载完全相同的`client`(含重试中间件)调试下载单个图片URL，原始字节原样保存到`save_path`
+    /// (不做任何格式转换)，返回状态码/字节数/猜测格式，供排查下载失败问题时单独验证某个URL
+    pub(crate) async fn debug_download_image(
+        &self,
+        url: &str,
+        save_path: &Path,
+    ) -> anyhow::Result<DebugDownloadImageResult> {
+        let http_res = self.img_client().get(url).send().await?;
+        let status_code = http_res.status().as_u16();
+        let image_data = http_res.bytes().await?;
+        let byte_count = image_data.len() as u64;
+        let guessed_format = image::guess_format(&image_data)
+            .ok()
+            .and_then(|format| format.extensions_str().first().copied())
+            .map(ToString::to_string);
+        save_image_atomic(save_path, &image_data)?;
+        Ok(DebugDownloadImageResult {
+            status_code,
+            byte_count,
+            guessed_format,
+            saved_path: save_path.to_string_lossy().to_string(),
+        })
+    }
+}
+
+/// 丢弃滑动窗口外的旧下载结果，保持`outcomes`只包含最近`window_secs`秒内的结果
+fn prune_outcome_window(outcomes: &mut VecDeque<(Instant, bool)>, now: Instant, window_secs: u64) {
+    while let Some(&(front, _)) = outcomes.front() {
+        if now.duration_since(front) > Duration::from_secs(window_secs) {
+            outcomes.pop_front();
+        } else {
+            break;
+        }
+    }
+}
+
+/// 判断`path`处是否已存在一张完整有效的图片：文件非空，且图片头部能被正常解析
+///
+/// 用于断点续传场景：半截写入或损坏的文件不会被误判为"已下载"
+fn is_valid_existing_image(path: &Path) -> bool {
+    match std::fs::metadata(path) {
+        Ok(metadata) if metadata.len() > 0 => image::image_dimensions(path).is_ok(),
+        _ => false,
+    }
+}
+
+/// 各`DownloadFormat`策略下可能保存的文件扩展名，断点续传时依次在这些扩展名中查找`stem`处是否已下载完成
+const KNOWN_IMAGE_EXTENSIONS: &[&str] = &["jpg", "png", "webp", "gif"];
+
+/// 在`stem`可能对应的各扩展名中查找一张已下载完整的图片，用于断点续传；
+/// 也供[`crate::commands::replace_chapter_page`]定位待替换的页面文件
+pub(crate) fn find_valid_existing_image(stem: &Path) -> Option<PathBuf> {
+    KNOWN_IMAGE_EXTENSIONS
+        .iter()
+        .map(|extension| stem.with_extension(extension))
+        .find(|path| is_valid_existing_image(path))
+}
+
+/// 根据下载格式策略，将原始图片字节转换为最终要保存的字节及对应的文件扩展名
+///
+/// `Original`模式不做任何转换，原样保存为其本身的格式；`Auto`模式下JPEG、GIF保持原样，
+/// PNG/WebP等无损格式转换为`quality`质量的JPEG以减小体积
+/// (`image`库目前只支持无损WebP编码，暂无法输出有损WebP，故用JPEG代替)
+fn resolve_download_format(
+    data: &[u8],
+    format: DownloadFormat,
+    quality: u8,
+) -> anyhow::Result<(Vec<u8>, &'static str)> {
+    let guessed = image::guess_format(data).ok();
+    if format == DownloadFormat::Original {
+        let extension = guessed
+            .and_then(|format| format.extensions_str().first())
+            .copied()
+            .unwrap_or("jpg");
+        return Ok((data.to_vec(), extension));
+    }
+
+    match guessed {
+        Some(image::ImageFormat::Png | image::ImageFormat::WebP) => {
+            let img = image::load_from_memory(data)?;
+            let rgb = image::DynamicImage::ImageRgb8(img.to_rgb8());
+            let mut output = std::io::Cursor::new(vec![]);
+            let encoder = image::codecs::jpeg::JpegEncoder::new_with_quality(&mut output, quality);
+            rgb.write_with_encoder(encoder)?;
+            Ok((output.into_inner(), "jpg"))
+        }
+        Some(image::ImageFormat::Gif) => Ok((data.to_vec(), "gif")),
+        None | Some(image::ImageFormat::Jpeg) => Ok((data.to_vec(), "jpg")),
+        Some(other) => {
+            let extension = other.extensions_str().first().copied().unwrap_or("jpg");
+            Ok((data.to_vec(), extension))
+        }
+    }
+}
+
+/// 保存图片，`Config.cross_episode_dedup_enabled`开启时会先查询[`ContentIndex`]：
+/// 若库中已存在内容完全相同的图片(如不同章节重复的封面)，则硬链接到那份文件而不是另存一份物理拷贝；
+/// 否则按原有逻辑正常写入，并把这份图片登记为该内容的规范路径
+///
+/// 硬链接对后续的导出(CBZ/PDF/EPUB/长图等均通过`std::fs::read`读取文件)完全透明，
+/// 因此不需要改动任何导出逻辑即可展开为完整章节
+fn save_image_with_dedup(app: &AppHandle, path: &Path, data: &[u8]) -> anyhow::Result<()> {
+    let dedup_enabled = app
+        .state::<RwLock<Config>>()
+        .read_or_panic()
+        .cross_episode_dedup_enabled;
+    if !dedup_enabled {
+        return save_image_atomic(path, data);
+    }
+
+    let canonical_path = app
+        .state::<RwLock<ContentIndex>>()
+        .read_or_panic()
+        .find_canonical_path(data)
+        .map(Path::to_path_buf);
+    if let Some(canonical_path) = canonical_path {
+        if hardlink_image(&canonical_path, path).is_ok() {
+            return Ok(());
+        }
+        // 硬链接失败(如规范路径与目标路径不在同一文件系统)，回退为写入完整副本
+    }
+
+    save_image_atomic(path, data)?;
+
+    let content_index = app.state::<RwLock<ContentIndex>>();
+    let mut content_index = content_index.write_or_panic();
+    content_index.record(data, path.to_path_buf());
+    let _ = content_index.save(app);
+
+    Ok(())
+}
+
+/// 把`canonical_path`硬链接为`target_path`：先硬链接到同目录下的临时文件再rename，
+/// 避免进程崩溃时在`target_path`处留下不完整的半截文件，与[`save_image_atomic`]的写入方式保持一致
+fn hardlink_image(canonical_path: &Path, target_path: &Path) -> anyhow::Result<()> {
+    let tmp_path = target_path.with_extension("tmp");
+    std::fs::hard_link(canonical_path, &tmp_path)
+        .with_context(|| format!("将`{canonical_path:?}`硬链接为`{tmp_path:?}`失败"))?;
+    std::fs::rename(&tmp_path, target_path)
+        .with_context(|| format!("将`{tmp_path:?}`重命名为`{target_path:?}`失败"))?;
+    Ok(())
+}
+
+/// 原子地把`data`写入`path`：先写入同目录下的临时文件，再rename到目标路径，
+/// 避免进程崩溃或被强制结束时在`path`处留下不完整的半截文件
+fn save_image_atomic(path: &Path, data: &[u8]) -> anyhow::Result<()> {
+    let tmp_path = path.with_extension("tmp");
+    std::fs::write(&tmp_path, data)
+        .with_context(|| format!("写入临时文件`{tmp_path:?}`失败"))?;
+    std::fs::rename(&tmp_path, path)
+        .with_context(|| format!("将`{tmp_path:?}`重命名为`{path:?}`失败"))?;
+    Ok(())
 }
 
 fn get_temp_download_dir(app: &AppHandle, ep: &Episode) -> PathBuf {
     let author = &ep.author;
     let comic_title = &ep.comic_title;
     let ep_title = &ep.ep_title;
-    let download_with_author = app
-        .state::<RwLock<Config>>()
-        .read_or_panic()
-        .download_with_author;
+    let (download_with_author, temp_dir_prefix) = {
+        let config = app.state::<RwLock<Config>>().read_or_panic();
+        (config.download_with_author, config.temp_dir_prefix.clone())
+    };
     let comic_title = if download_with_author {
         &format!("[{author}] {comic_title}")
     } else {
         &ep.comic_title
     };
-    app.state::<RwLock<Config>>()
-        .read_or_panic()
-        .download_dir
+    let base_dir = ep.target_dir.clone().unwrap_or_else(|| {
+        app.state::<RwLock<Config>>()
+            .read_or_panic()
+            .download_dir
+            .clone()
+    });
+    base_dir
         .join(comic_title)
-        .join(format!(".下载中-{ep_title}")) // 以 `.下载中-` 开头，表示是临时目录
+        .join(format!("{temp_dir_prefix}{ep_title}")) // 以`temp_dir_prefix`开头，表示是临时目录
+}
+
+/// Windows上以`.`开头的目录并不会被自动隐藏，容易被用户误打开看到正在下载的不完整文件，
+/// 因此这里额外调用`attrib`显式设置隐藏属性；其他平台上`.`前缀本身就足够隐藏，无需处理
+#[cfg(target_os = "windows")]
+fn hide_temp_download_dir(dir: &Path) {
+    if let Err(err) = std::process::Command::new("attrib").arg("+h").arg(dir).status() {
+        eprintln!("warn: 为临时目录`{dir:?}`设置隐藏属性失败: {err}");
+    }
 }
 
 fn emit_start_event(app: &AppHandle, ep_id: String, title: String, total: u32) {
@@ -343,39 +1082,56 @@ fn emit_start_event(app: &AppHandle, ep_id: String, title: String, total: u32) {
         total,
     };
     let event = events::DownloadEpisodeStartEvent(payload);
-    let _ = event.emit(app);
+    crate::events::emit_event(app, event);
 }
 
 fn emit_pending_event(app: &AppHandle, ep_id: String, title: String) {
-    let payload = events::DownloadEpisodePendingEventPayload { ep_id, title };
+    let payload = events::DownloadEpisodePendingEventPayload {
+        ep_id,
+        title,
+        created_at: Utc::now(),
+    };
     let event = events::DownloadEpisodePendingEvent(payload);
-    let _ = event.emit(app);
+    crate::events::emit_event(app, event);
 }
 
-fn emit_success_event(app: &AppHandle, ep_id: String, url: String, downloaded_count: u32) {
+fn emit_success_event(
+    app: &AppHandle,
+    ep_id: String,
+    url: String,
+    downloaded_count: u32,
+    bytes_per_sec: f64,
+) {
     let payload = events::DownloadImageSuccessEventPayload {
         ep_id,
         url,
         downloaded_count,
+        bytes_per_sec,
     };
     let event = events::DownloadImageSuccessEvent(payload);
-    let _ = event.emit(app);
+    crate::events::emit_event(app, event);
 }
 
 fn emit_error_event(app: &AppHandle, ep_id: String, url: String, err_msg: String) {
+    crate::log::log_event(
+        app,
+        &app.state::<FrontendLogState>(),
+        LogLevel::Error,
+        format!("下载`{url}`失败: {err_msg}"),
+    );
     let payload = events::DownloadImageErrorEventPayload {
         ep_id,
         url,
         err_msg,
     };
     let event = events::DownloadImageErrorEvent(payload);
-    let _ = event.emit(app);
+    crate::events::emit_event(app, event);
 }
 
 fn emit_end_event(app: &AppHandle, ep_id: String, err_msg: Option<String>) {
     let payload = events::DownloadEpisodeEndEventPayload { ep_id, err_msg };
     let event = events::DownloadEpisodeEndEvent(payload);
-    let _ = event.emit(app);
+    crate::events::emit_event(app, event);
 }
 
 #[allow(clippy::cast_lossless)]
@@ -391,11 +1147,136 @@ fn emit_update_overall_progress_event(
         percentage,
     };
     let event = events::UpdateOverallDownloadProgressEvent(payload);
-    let _ = event.emit(app);
+    crate::events::emit_event(app, event);
 }
 
 fn emit_download_speed_event(app: &AppHandle, speed: String) {
     let payload = DownloadSpeedEventPayload { speed };
     let event = DownloadSpeedEvent(payload);
-    let _ = event.emit(app);
+    crate::events::emit_event(app, event);
+}
+
+fn emit_download_paused_event(app: &AppHandle, reason: String) {
+    let payload = DownloadPausedEventPayload { reason };
+    let event = DownloadPausedEvent(payload);
+    crate::events::emit_event(app, event);
+}
+
+/// 广播一次限速等待，`kind`取值见[`DownloadWaitEventPayload::kind`]
+fn emit_wait_event(app: &AppHandle, ep_id: String, kind: &'static str, duration: Duration) {
+    let payload = DownloadWaitEventPayload {
+        ep_id,
+        kind: kind.to_string(),
+        secs: duration.as_secs_f64(),
+    };
+    let event = DownloadWaitEvent(payload);
+    crate::events::emit_event(app, event);
+}
+
+#[allow(clippy::cast_lossless)]
+fn to_comic_download_progress(comic_id: &str, entry: &ComicProgressEntry) -> ComicDownloadProgress {
+    let percentage = entry.completed_episode_count as f64 / entry.total_episode_count as f64 * 100.0;
+    ComicDownloadProgress {
+        comic_id: comic_id.to_string(),
+        comic_title: entry.comic_title.clone(),
+        total_episode_count: entry.total_episode_count,
+        completed_episode_count: entry.completed_episode_count,
+        percentage,
+    }
+}
+
+fn emit_comic_download_progress_event(app: &AppHandle, comic_id: &str, entry: &ComicProgressEntry) {
+    let progress = to_comic_download_progress(comic_id, entry);
+    let payload = events::ComicDownloadProgressEventPayload {
+        comic_id: progress.comic_id,
+        comic_title: progress.comic_title,
+        total_episode_count: progress.total_episode_count,
+        completed_episode_count: progress.completed_episode_count,
+        percentage: progress.percentage,
+    };
+    let event = events::ComicDownloadProgressEvent(payload);
+    crate::events::emit_event(app, event);
+}
+
+fn emit_auto_power_countdown_event(
+    app: &AppHandle,
+    action: AutoPowerAction,
+    seconds_remaining: u32,
+    cancelled: bool,
+) {
+    let payload = AutoPowerCountdownEventPayload {
+        action,
+        seconds_remaining,
+        cancelled,
+    };
+    let event = AutoPowerCountdownEvent(payload);
+    crate::events::emit_event(app, event);
+}
+
+/// 倒计时结束后真正执行电源操作，当前平台不支持时打印警告并放弃
+fn execute_power_action(action: AutoPowerAction) {
+    let command = match action {
+        AutoPowerAction::Off => return,
+        AutoPowerAction::Sleep => sleep_command(),
+        AutoPowerAction::Shutdown => shutdown_command(),
+    };
+    let Some(mut command) = command else {
+        eprintln!("warn: 当前平台不支持自动执行电源操作: {action:?}");
+        return;
+    };
+    if let Err(err) = command.spawn() {
+        eprintln!("warn: 执行电源操作失败: {action:?}, {err}");
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn sleep_command() -> Option<std::process::Command> {
+    let mut command = std::process::Command::new("rundll32.exe");
+    command.args(["powrprof.dll,SetSuspendState", "0", "1", "0"]);
+    Some(command)
+}
+
+#[cfg(target_os = "windows")]
+fn shutdown_command() -> Option<std::process::Command> {
+    let mut command = std::process::Command::new("shutdown.exe");
+    command.args(["/s", "/t", "0"]);
+    Some(command)
+}
+
+#[cfg(target_os = "macos")]
+fn sleep_command() -> Option<std::process::Command> {
+    let mut command = std::process::Command::new("pmset");
+    command.arg("sleepnow");
+    Some(command)
+}
+
+#[cfg(target_os = "macos")]
+fn shutdown_command() -> Option<std::process::Command> {
+    let mut command = std::process::Command::new("shutdown");
+    command.args(["-h", "now"]);
+    Some(command)
+}
+
+#[cfg(target_os = "linux")]
+fn sleep_command() -> Option<std::process::Command> {
+    let mut command = std::process::Command::new("systemctl");
+    command.arg("suspend");
+    Some(command)
+}
+
+#[cfg(target_os = "linux")]
+fn shutdown_command() -> Option<std::process::Command> {
+    let mut command = std::process::Command::new("systemctl");
+    command.arg("poweroff");
+    Some(command)
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+fn sleep_command() -> Option<std::process::Command> {
+    None
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+fn shutdown_command() -> Option<std::process::Command> {
+    None
 }
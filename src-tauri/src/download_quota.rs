@@ -0,0 +1,72 @@
+use std::path::PathBuf;
+
+use anyhow::Context;
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+/// 每日下载量配额的计数器，持久化到磁盘避免重启应用绕过配额限制；
+/// 配合`Config.daily_image_quota`/`daily_episode_quota`使用，见[`crate::download_manager::DownloadManager`]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DownloadQuotaStore {
+    /// 计数所属的日期(本地时区`YYYY-MM-DD`)，与当前日期不同时会在下次访问前自动重置计数
+    date: String,
+    downloaded_image_count: u32,
+    downloaded_episode_count: u32,
+}
+
+impl DownloadQuotaStore {
+    pub fn new(app: &AppHandle) -> anyhow::Result<Self> {
+        let path = Self::path(app)?;
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let string = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&string).unwrap_or_default())
+    }
+
+    /// 若计数所属日期不是今天，重置为今天的空计数
+    fn roll_over_if_needed(&mut self) {
+        let today = Local::now().format("%Y-%m-%d").to_string();
+        if self.date != today {
+            self.date = today;
+            self.downloaded_image_count = 0;
+            self.downloaded_episode_count = 0;
+        }
+    }
+
+    pub fn image_count(&mut self) -> u32 {
+        self.roll_over_if_needed();
+        self.downloaded_image_count
+    }
+
+    pub fn episode_count(&mut self) -> u32 {
+        self.roll_over_if_needed();
+        self.downloaded_episode_count
+    }
+
+    pub fn record_image(&mut self) {
+        self.roll_over_if_needed();
+        self.downloaded_image_count += 1;
+    }
+
+    pub fn record_episode(&mut self) {
+        self.roll_over_if_needed();
+        self.downloaded_episode_count += 1;
+    }
+
+    pub fn save(&self, app: &AppHandle) -> anyhow::Result<()> {
+        let path = Self::path(app)?;
+        let string = serde_json::to_string_pretty(self)?;
+        std::fs::write(path, string)?;
+        Ok(())
+    }
+
+    fn path(app: &AppHandle) -> anyhow::Result<PathBuf> {
+        let app_data_dir = app
+            .path()
+            .app_data_dir()
+            .context("failed to get app data dir")?;
+        Ok(app_data_dir.join("download_quota.json"))
+    }
+}
@@ -0,0 +1,157 @@
+use std::collections::HashMap;
+
+use serde::Serialize;
+use specta::Type;
+
+use crate::download_manager::DownloadFormat;
+use crate::export::{ExportConflictPolicy, GrayscaleMode, LongStripFormat};
+use crate::types::{ApiChannel, DefaultChapterSelection, ImageQuality, Language, Sort};
+
+/// 某个枚举取值在下拉框中的展示信息：`value`与该取值序列化后传给命令参数的字符串完全一致，
+/// `label`是按[`Language`]翻译好的展示文案，见[`crate::commands::get_enums_meta`]
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct EnumOption {
+    pub value: String,
+    pub label: String,
+}
+
+/// 枚举名与取值到中/英文展示文案的对照表：前端新增一处下拉框，只需在这里补充一行，
+/// 不必在前端再手写维护一份同样的常量，见[`crate::commands::get_enums_meta`]
+const LABELS: &[(&str, &str, &str, &str)] = &[
+    // (枚举名, 取值, 中文文案, 英文文案)
+    ("Sort", "Default", "默认排序", "Default"),
+    ("Sort", "TimeNewest", "最新发布", "Newest"),
+    ("Sort", "TimeOldest", "最早发布", "Oldest"),
+    ("Sort", "LikeMost", "最多喜欢", "Most Liked"),
+    ("Sort", "ViewMost", "最多浏览", "Most Viewed"),
+    ("DownloadFormat", "Original", "保留原始格式", "Original"),
+    ("DownloadFormat", "Auto", "智能压缩", "Auto"),
+    ("GrayscaleMode", "Off", "不转换", "Off"),
+    ("GrayscaleMode", "Auto", "自动检测", "Auto"),
+    ("GrayscaleMode", "Force", "强制灰度", "Force"),
+    ("ExportConflictPolicy", "Overwrite", "覆盖", "Overwrite"),
+    ("ExportConflictPolicy", "Skip", "跳过", "Skip"),
+    ("ExportConflictPolicy", "Rename", "自动重命名", "Rename"),
+    ("LongStripFormat", "Png", "PNG", "PNG"),
+    ("LongStripFormat", "WebP", "WebP", "WebP"),
+    ("ApiChannel", "One", "线路一", "Channel 1"),
+    ("ApiChannel", "Two", "线路二", "Channel 2"),
+    ("ApiChannel", "Three", "线路三", "Channel 3"),
+    ("ImageQuality", "Original", "原图", "Original"),
+    ("ImageQuality", "High", "高质量", "High"),
+    ("ImageQuality", "Medium", "中等质量", "Medium"),
+    ("ImageQuality", "Low", "低质量", "Low"),
+    ("DefaultChapterSelection", "All", "全部勾选", "Select All"),
+    (
+        "DefaultChapterSelection",
+        "Undownloaded",
+        "仅未下载",
+        "Undownloaded Only",
+    ),
+    ("DefaultChapterSelection", "LatestOnly", "仅最新一话", "Latest Only"),
+];
+
+/// 把`value`序列化为其serde tag字符串，复用`serde_json`以保证与命令实际接收到的值完全一致，
+/// 不必为每个枚举手写一遍`as_str`
+fn variant_tag<T: Serialize>(value: &T) -> String {
+    match serde_json::to_value(value) {
+        Ok(serde_json::Value::String(s)) => s,
+        _ => String::new(),
+    }
+}
+
+/// 把一组枚举取值转换为下拉框选项：`label`从[`LABELS`]按`enum_name`+`value`查找，
+/// 未登记的取值退化为直接显示其`value`
+fn options<T: Serialize>(language: Language, enum_name: &str, variants: &[T]) -> Vec<EnumOption> {
+    variants
+        .iter()
+        .map(|variant| {
+            let value = variant_tag(variant);
+            let label = LABELS
+                .iter()
+                .find(|(name, val, _, _)| *name == enum_name && *val == value)
+                .map(|(_, _, zh, en)| match language {
+                    Language::Zh => (*zh).to_string(),
+                    Language::En => (*en).to_string(),
+                })
+                .unwrap_or_else(|| value.clone());
+            EnumOption { value, label }
+        })
+        .collect()
+}
+
+/// 生成前端渲染下拉框所需的枚举元数据，key为前端约定的camelCase枚举名，
+/// value为按`language`翻译好的选项列表；新增一个枚举的下拉框支持时，在此函数里补充一行即可
+pub fn enums_meta(language: Language) -> HashMap<String, Vec<EnumOption>> {
+    let mut meta = HashMap::new();
+    meta.insert(
+        "sort".to_string(),
+        options(
+            language,
+            "Sort",
+            &[
+                Sort::Default,
+                Sort::TimeNewest,
+                Sort::TimeOldest,
+                Sort::LikeMost,
+                Sort::ViewMost,
+            ],
+        ),
+    );
+    meta.insert(
+        "downloadFormat".to_string(),
+        options(language, "DownloadFormat", &[DownloadFormat::Original, DownloadFormat::Auto]),
+    );
+    meta.insert(
+        "grayscaleMode".to_string(),
+        options(
+            language,
+            "GrayscaleMode",
+            &[GrayscaleMode::Off, GrayscaleMode::Auto, GrayscaleMode::Force],
+        ),
+    );
+    meta.insert(
+        "exportConflictPolicy".to_string(),
+        options(
+            language,
+            "ExportConflictPolicy",
+            &[
+                ExportConflictPolicy::Overwrite,
+                ExportConflictPolicy::Skip,
+                ExportConflictPolicy::Rename,
+            ],
+        ),
+    );
+    meta.insert(
+        "longStripFormat".to_string(),
+        options(language, "LongStripFormat", &[LongStripFormat::Png, LongStripFormat::WebP]),
+    );
+    meta.insert("apiChannel".to_string(), options(language, "ApiChannel", &ApiChannel::all()));
+    meta.insert(
+        "imageQuality".to_string(),
+        options(
+            language,
+            "ImageQuality",
+            &[
+                ImageQuality::Original,
+                ImageQuality::High,
+                ImageQuality::Medium,
+                ImageQuality::Low,
+            ],
+        ),
+    );
+    meta.insert(
+        "defaultChapterSelection".to_string(),
+        options(
+            language,
+            "DefaultChapterSelection",
+            &[
+                DefaultChapterSelection::All,
+                DefaultChapterSelection::Undownloaded,
+                DefaultChapterSelection::LatestOnly,
+            ],
+        ),
+    );
+    meta
+}
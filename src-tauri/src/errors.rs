@@ -20,3 +20,8 @@ impl From<anyhow::Error> for CommandError {
         Self(err.to_string_chain())
     }
 }
+impl std::fmt::Display for CommandError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
@@ -1,11 +1,40 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use specta::Type;
+use tauri::{AppHandle, Manager};
 use tauri_specta::Event;
 
+/// `emit_event`发送失败的次数统计，注册为app state，暴露在[`crate::commands::get_app_info`]里，
+/// 排查"前端收不到下载进度/日志"一类问题时，可以先看这个计数是否在增长
+#[derive(Default)]
+pub struct EmitFailureStats(AtomicU64);
+
+impl EmitFailureStats {
+    pub fn count(&self) -> u64 {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// 统一的事件发送封装，替代各处`let _ = event.emit(app)`：失败时打印warn日志并计入[`EmitFailureStats`]，
+/// 而不是把错误悄悄吞掉
+pub fn emit_event<E: Event>(app: &AppHandle, event: E) {
+    if let Err(err) = event.emit(app) {
+        eprintln!("warn: 事件发送失败: {err}");
+        app.state::<EmitFailureStats>().0.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
 pub mod prelude {
     pub use crate::events::{
-        DownloadEpisodeEndEvent, DownloadEpisodePendingEvent, DownloadEpisodeStartEvent,
-        DownloadImageErrorEvent, DownloadImageSuccessEvent, DownloadSpeedEvent,
+        ArchiveVolumeCreatedEvent, AutoPowerCountdownEvent, ClipboardComicFoundEvent,
+        ComicDownloadProgressEvent, DownloadDirUnwritableEvent, DownloadEpisodeEndEvent,
+        DownloadEpisodePendingEvent, DownloadEpisodeStartEvent, DownloadImageErrorEvent,
+        DownloadImageSuccessEvent,
+        DownloadPausedEvent, DownloadSpeedEvent, DownloadWaitEvent, ExportFileSkippedEvent,
+        ExportLongStripProgressEvent, ExportZipProgressEvent, HealthCheckProgressEvent,
+        LibraryDirSwitchedEvent, LogEvent, NewTagComicFoundEvent, TranscodeProgressEvent,
         UpdateOverallDownloadProgressEvent,
     };
 }
@@ -15,6 +44,8 @@ pub mod prelude {
 pub struct DownloadEpisodePendingEventPayload {
     pub ep_id: String,
     pub title: String,
+    /// 任务创建时间，供前端按创建时间排序任务列表
+    pub created_at: DateTime<Utc>,
 }
 #[derive(Serialize, Deserialize, Clone, Type, Event)]
 pub struct DownloadEpisodePendingEvent(pub DownloadEpisodePendingEventPayload);
@@ -35,6 +66,8 @@ pub struct DownloadImageSuccessEventPayload {
     pub ep_id: String,
     pub url: String,
     pub downloaded_count: u32,
+    /// 该章节从开始下载到现在的平均下载速度(字节/秒)，供前端按速度排序任务列表
+    pub bytes_per_sec: f64,
 }
 #[derive(Serialize, Deserialize, Clone, Type, Event)]
 pub struct DownloadImageSuccessEvent(pub DownloadImageSuccessEventPayload);
@@ -75,3 +108,162 @@ pub struct DownloadSpeedEventPayload {
 }
 #[derive(Serialize, Deserialize, Clone, Type, Event)]
 pub struct DownloadSpeedEvent(pub DownloadSpeedEventPayload);
+
+#[derive(Serialize, Deserialize, Clone, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct HealthCheckProgressEventPayload {
+    pub item_name: String,
+    pub current: u32,
+    pub total: u32,
+}
+#[derive(Serialize, Deserialize, Clone, Type, Event)]
+pub struct HealthCheckProgressEvent(pub HealthCheckProgressEventPayload);
+
+#[derive(Serialize, Deserialize, Clone, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportFileSkippedEventPayload {
+    pub path: String,
+}
+#[derive(Serialize, Deserialize, Clone, Type, Event)]
+pub struct ExportFileSkippedEvent(pub ExportFileSkippedEventPayload);
+
+#[derive(Serialize, Deserialize, Clone, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct DownloadPausedEventPayload {
+    /// 暂停原因：不在允许下载的时间段内、当日下载配额已用完，或下载失败触发了熔断
+    pub reason: String,
+}
+#[derive(Serialize, Deserialize, Clone, Type, Event)]
+pub struct DownloadPausedEvent(pub DownloadPausedEventPayload);
+
+#[derive(Serialize, Deserialize, Clone, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct DownloadWaitEventPayload {
+    pub ep_id: String,
+    /// 当前所处的限速等待粒度: `image`(同一章节内等待下一张图片)、`episode`(同一漫画内等待下一章节)、
+    /// `comic`(切换到下一本漫画前的等待)，分别对应`Config`里的三档休眠配置
+    pub kind: String,
+    /// 本次等待的时长(秒)
+    pub secs: f64,
+}
+#[derive(Serialize, Deserialize, Clone, Type, Event)]
+pub struct DownloadWaitEvent(pub DownloadWaitEventPayload);
+
+#[derive(Serialize, Deserialize, Clone, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ComicDownloadProgressEventPayload {
+    pub comic_id: String,
+    pub comic_title: String,
+    pub total_episode_count: u32,
+    pub completed_episode_count: u32,
+    pub percentage: f64,
+}
+#[derive(Serialize, Deserialize, Clone, Type, Event)]
+pub struct ComicDownloadProgressEvent(pub ComicDownloadProgressEventPayload);
+
+#[derive(Serialize, Deserialize, Clone, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportLongStripProgressEventPayload {
+    pub ep_title: String,
+    pub current: u32,
+    pub total: u32,
+}
+#[derive(Serialize, Deserialize, Clone, Type, Event)]
+pub struct ExportLongStripProgressEvent(pub ExportLongStripProgressEventPayload);
+
+#[derive(Serialize, Deserialize, Clone, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportZipProgressEventPayload {
+    pub comic_title: String,
+    pub current: u32,
+    pub total: u32,
+}
+#[derive(Serialize, Deserialize, Clone, Type, Event)]
+pub struct ExportZipProgressEvent(pub ExportZipProgressEventPayload);
+
+#[derive(Serialize, Deserialize, Clone, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct AutoPowerCountdownEventPayload {
+    pub action: crate::types::AutoPowerAction,
+    /// 距离执行`action`还剩多少秒，归零时即将执行
+    pub seconds_remaining: u32,
+    /// 倒计时是否已被取消(手动取消，或队列在倒计时期间又有了新任务)，
+    /// 为`true`时`seconds_remaining`的值不再有意义
+    pub cancelled: bool,
+}
+#[derive(Serialize, Deserialize, Clone, Type, Event)]
+pub struct AutoPowerCountdownEvent(pub AutoPowerCountdownEventPayload);
+
+/// 剪贴板监听(`Config.clipboard_watcher_enabled`)检测到合法的漫画id/链接时发出，
+/// 见[`crate::clipboard_watcher`]
+#[derive(Serialize, Deserialize, Clone, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ClipboardComicFoundEventPayload {
+    pub comic_id: String,
+}
+#[derive(Serialize, Deserialize, Clone, Type, Event)]
+pub struct ClipboardComicFoundEvent(pub ClipboardComicFoundEventPayload);
+
+#[derive(Serialize, Deserialize, Clone, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscodeProgressEventPayload {
+    pub comic_title: String,
+    pub current: u32,
+    pub total: u32,
+}
+#[derive(Serialize, Deserialize, Clone, Type, Event)]
+pub struct TranscodeProgressEvent(pub TranscodeProgressEventPayload);
+
+#[derive(Serialize, Deserialize, Clone, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveVolumeCreatedEventPayload {
+    pub comic_title: String,
+    /// 从1开始计数
+    pub volume_index: u32,
+    pub path: String,
+}
+#[derive(Serialize, Deserialize, Clone, Type, Event)]
+pub struct ArchiveVolumeCreatedEvent(pub ArchiveVolumeCreatedEventPayload);
+
+#[derive(Serialize, Deserialize, Clone, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct LogEventPayload {
+    pub level: crate::log::LogLevel,
+    pub message: String,
+}
+#[derive(Serialize, Deserialize, Clone, Type, Event)]
+pub struct LogEvent(pub LogEventPayload);
+
+#[derive(Serialize, Deserialize, Clone, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct DownloadDirUnwritableEventPayload {
+    pub download_dir: String,
+    pub message: String,
+    pub suggestion: String,
+}
+#[derive(Serialize, Deserialize, Clone, Type, Event)]
+pub struct DownloadDirUnwritableEvent(pub DownloadDirUnwritableEventPayload);
+
+/// tag订阅调度器发现新作时发出，见[`crate::commands::check_tag_subscriptions`]
+#[derive(Serialize, Deserialize, Clone, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct NewTagComicFoundEventPayload {
+    pub tag: String,
+    pub comic_id: String,
+    pub title: String,
+    pub author: String,
+}
+#[derive(Serialize, Deserialize, Clone, Type, Event)]
+pub struct NewTagComicFoundEvent(pub NewTagComicFoundEventPayload);
+
+/// 因剩余空间不足自动切换下载目录时发出，见[`crate::commands::resolve_download_target_dir`]
+#[derive(Serialize, Deserialize, Clone, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryDirSwitchedEventPayload {
+    pub comic_title: String,
+    pub from_label: String,
+    pub to_label: String,
+    pub to_dir: String,
+}
+#[derive(Serialize, Deserialize, Clone, Type, Event)]
+pub struct LibraryDirSwitchedEvent(pub LibraryDirSwitchedEventPayload);
@@ -0,0 +1,689 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use printpdf::{
+    FontId, Mm, Op, ParsedFont, PdfDocument, PdfPage, PdfSaveOptions, Point, Pt, RawImage,
+    TextItem, XObjectTransform,
+};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+/// 导出时是否将图片转换为灰度，以减小PDF/CBZ体积
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub enum GrayscaleMode {
+    /// 不做任何转换
+    #[default]
+    Off,
+    /// 自动检测，仅对本来就接近灰度的图片(黑白漫画常见)做转换
+    Auto,
+    /// 无论图片是否为彩色，强制转换为灰度
+    Force,
+}
+
+/// 按需把图片转换为灰度JPEG，返回转换后的图片字节
+///
+/// `Off`模式直接返回原始字节；`Auto`模式会先采样判断图片是否接近灰度，只有接近灰度的图片才会被转换(不论源格式，转换后均为灰度JPEG)
+pub fn maybe_grayscale(bytes: &[u8], mode: GrayscaleMode) -> anyhow::Result<Vec<u8>> {
+    if mode == GrayscaleMode::Off {
+        return Ok(bytes.to_vec());
+    }
+
+    let img = image::load_from_memory(bytes)?;
+    if mode == GrayscaleMode::Auto && !is_mostly_grayscale(&img) {
+        return Ok(bytes.to_vec());
+    }
+
+    let gray = image::DynamicImage::ImageLuma8(img.to_luma8());
+    let mut output = std::io::Cursor::new(vec![]);
+    gray.write_to(&mut output, image::ImageFormat::Jpeg)?;
+    Ok(output.into_inner())
+}
+
+/// 采样图片的像素，判断其是否接近灰度(R、G、B三通道差值很小)
+fn is_mostly_grayscale(img: &image::DynamicImage) -> bool {
+    let rgb = img.to_rgb8();
+    let pixels: Vec<_> = rgb.pixels().step_by(97).collect();
+    if pixels.is_empty() {
+        return true;
+    }
+    let colorful_count = pixels
+        .iter()
+        .filter(|p| {
+            let [r, g, b] = p.0;
+            let max = r.max(g).max(b);
+            let min = r.min(g).min(b);
+            max - min > 12
+        })
+        .count();
+    // 彩色像素占比很低，就认为这是一张被存成彩色的黑白图
+    (colorful_count * 100 / pixels.len()) < 5
+}
+
+/// 写入PDF文档信息字典的元数据
+pub struct PdfMetadata {
+    pub title: String,
+    pub author: String,
+    pub subject: String,
+    pub keywords: Vec<String>,
+}
+
+/// PDF页码与章节名页眉的叠加层配置
+///
+/// 叠加文本需要能显示中文的字体，而本程序不内置任何字体，所以必须由用户在`font_path`中指定一个本地字体文件；
+/// 未指定字体时，即使`page_number`或`chapter_header`为`true`，也不会叠加任何文本
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct PdfOverlayConfig {
+    /// 是否在每页底部叠加页码
+    pub page_number: bool,
+    /// 是否在每页顶部叠加章节名页眉
+    pub chapter_header: bool,
+    /// 叠加文本所用的字体文件路径(ttf/otf)
+    pub font_path: Option<PathBuf>,
+}
+
+/// 导出文件已存在时的处理策略
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub enum ExportConflictPolicy {
+    /// 覆盖已存在的文件
+    Overwrite,
+    /// 跳过，保留已存在的文件
+    Skip,
+    /// 自动在文件名后加序号，例如 `xxx (1).cbz`
+    #[default]
+    Rename,
+}
+
+/// 把一组图片按顺序排版为一个PDF文档，每张图片占一页，并写入文档属性
+///
+/// `overlay`开启且配置了字体时，会在每页叠加页码和/或章节名页眉，页眉文本固定为`metadata.subject`
+///
+/// 图片的读取、灰度转换、解码彼此独立且是CPU/IO密集操作，用rayon并行处理；
+/// 解码结果汇总后，向`PdfDocument`添加图片、排版页面仍在单线程中按原始顺序完成
+pub fn images_to_pdf(
+    image_paths: &[PathBuf],
+    metadata: &PdfMetadata,
+    grayscale_mode: GrayscaleMode,
+    overlay: &PdfOverlayConfig,
+) -> anyhow::Result<Vec<u8>> {
+    let mut doc = PdfDocument::new(&metadata.title);
+    doc.metadata.info.author = metadata.author.clone();
+    doc.metadata.info.subject = metadata.subject.clone();
+    doc.metadata.info.keywords = metadata.keywords.clone();
+    doc.metadata.info.creator = format!("picacomic-downloader {}", env!("CARGO_PKG_VERSION"));
+
+    let font_id = load_overlay_font(&mut doc, overlay)?;
+
+    let decoded_images: Vec<RawImage> = image_paths
+        .par_iter()
+        .map(|image_path| -> anyhow::Result<RawImage> {
+            let bytes = std::fs::read(image_path)
+                .with_context(|| format!("读取图片`{image_path:?}`失败"))?;
+            let bytes = maybe_grayscale(&bytes, grayscale_mode)
+                .with_context(|| format!("将图片`{image_path:?}`转换为灰度失败"))?;
+            let mut warnings = vec![];
+            RawImage::decode_from_bytes(&bytes, &mut warnings)
+                .map_err(|e| anyhow::anyhow!("解析图片`{image_path:?}`失败: {e}"))
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    let total_pages = decoded_images.len();
+    let mut pages = Vec::with_capacity(total_pages);
+    for (index, image) in decoded_images.into_iter().enumerate() {
+        // 以96dpi把像素换算为毫米，作为页面尺寸
+        let px_to_mm = |px: usize| px as f32 * 25.4 / 96.0;
+        let width = Mm(px_to_mm(image.width));
+        let height = Mm(px_to_mm(image.height));
+
+        let image_id = doc.add_image(&image);
+        let mut ops = vec![Op::UseXObject {
+            id: image_id,
+            transform: XObjectTransform::default(),
+        }];
+        if let Some(font_id) = &font_id {
+            ops.extend(overlay_ops(
+                overlay,
+                &metadata.subject,
+                index + 1,
+                total_pages,
+                font_id,
+                width,
+                height,
+            ));
+        }
+        pages.push(PdfPage::new(width, height, ops));
+    }
+
+    let mut warnings = vec![];
+    let pdf_bytes = doc
+        .with_pages(pages)
+        .save(&PdfSaveOptions::default(), &mut warnings);
+
+    Ok(pdf_bytes)
+}
+
+/// 读取`overlay.font_path`并注册为PDF内嵌字体；未配置字体或未开启任何叠加开关时返回`None`
+fn load_overlay_font(doc: &mut PdfDocument, overlay: &PdfOverlayConfig) -> anyhow::Result<Option<FontId>> {
+    if !overlay.page_number && !overlay.chapter_header {
+        return Ok(None);
+    }
+    let Some(font_path) = &overlay.font_path else {
+        return Ok(None);
+    };
+    let font_bytes =
+        std::fs::read(font_path).with_context(|| format!("读取叠加层字体`{font_path:?}`失败"))?;
+    let mut warnings = vec![];
+    let font = ParsedFont::from_bytes(&font_bytes, 0, &mut warnings)
+        .ok_or_else(|| anyhow::anyhow!("解析叠加层字体`{font_path:?}`失败"))?;
+    Ok(Some(doc.add_font(&font)))
+}
+
+/// 生成在某一页叠加页码/章节页眉所需的文本绘制指令
+fn overlay_ops(
+    overlay: &PdfOverlayConfig,
+    chapter_title: &str,
+    page_number: usize,
+    total_pages: usize,
+    font_id: &FontId,
+    page_width: Mm,
+    page_height: Mm,
+) -> Vec<Op> {
+    const FONT_SIZE: Pt = Pt(10.0);
+    let mut ops = vec![Op::StartTextSection];
+    if overlay.chapter_header {
+        ops.push(Op::SetFontSize {
+            size: FONT_SIZE,
+            font: font_id.clone(),
+        });
+        ops.push(Op::SetTextCursor {
+            pos: Point::new(Mm(5.0), Mm(page_height.0 - 8.0)),
+        });
+        ops.push(Op::WriteText {
+            items: vec![TextItem::Text(chapter_title.to_string())],
+            font: font_id.clone(),
+        });
+    }
+    if overlay.page_number {
+        ops.push(Op::SetFontSize {
+            size: FONT_SIZE,
+            font: font_id.clone(),
+        });
+        ops.push(Op::SetTextCursor {
+            pos: Point::new(Mm(page_width.0 / 2.0), Mm(5.0)),
+        });
+        ops.push(Op::WriteText {
+            items: vec![TextItem::Text(format!("{page_number} / {total_pages}"))],
+            font: font_id.clone(),
+        });
+    }
+    ops.push(Op::EndTextSection);
+    ops
+}
+
+/// 长图导出的输出图片格式
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub enum LongStripFormat {
+    #[default]
+    Png,
+    WebP,
+}
+
+/// 把一组图片按顺序纵向拼接为长图，超过`max_height`时自动切分为多张，按切分后的顺序返回输出路径
+///
+/// 每次只在内存中保留当前这一块尚未写盘的图片，写盘后立即释放，避免一次性加载全部图片导致内存占用过大。
+/// `on_progress`在每处理完一张原始图片后被调用一次，参数为`(已处理数量, 总数量)`，供调用方上报进度事件
+pub fn export_long_strip(
+    image_paths: &[PathBuf],
+    output_dir: &Path,
+    stem: &str,
+    max_height: u32,
+    format: LongStripFormat,
+    grayscale_mode: GrayscaleMode,
+    mut on_progress: impl FnMut(usize, usize),
+) -> anyhow::Result<Vec<PathBuf>> {
+    let total = image_paths.len();
+    let mut output_paths = vec![];
+    let mut chunk_rows: Vec<image::RgbaImage> = vec![];
+    let mut chunk_width: u32 = 0;
+    let mut chunk_height: u32 = 0;
+    let mut chunk_index: u32 = 1;
+
+    for (i, path) in image_paths.iter().enumerate() {
+        let bytes = std::fs::read(path).with_context(|| format!("读取图片`{path:?}`失败"))?;
+        let bytes = maybe_grayscale(&bytes, grayscale_mode)
+            .with_context(|| format!("将图片`{path:?}`转换为灰度失败"))?;
+        let img = image::load_from_memory(&bytes)
+            .with_context(|| format!("解析图片`{path:?}`失败"))?
+            .to_rgba8();
+
+        // 加入当前图片会超出最大高度时，先把已有的图片落盘成一块，再开始新的一块
+        if !chunk_rows.is_empty() && chunk_height + img.height() > max_height {
+            let output_path = flush_long_strip_chunk(
+                &chunk_rows,
+                chunk_width,
+                chunk_height,
+                output_dir,
+                stem,
+                chunk_index,
+                format,
+            )?;
+            output_paths.push(output_path);
+            chunk_rows.clear();
+            chunk_width = 0;
+            chunk_height = 0;
+            chunk_index += 1;
+        }
+
+        chunk_width = chunk_width.max(img.width());
+        chunk_height += img.height();
+        chunk_rows.push(img);
+
+        on_progress(i + 1, total);
+    }
+
+    if !chunk_rows.is_empty() {
+        let output_path = flush_long_strip_chunk(
+            &chunk_rows,
+            chunk_width,
+            chunk_height,
+            output_dir,
+            stem,
+            chunk_index,
+            format,
+        )?;
+        output_paths.push(output_path);
+    }
+
+    Ok(output_paths)
+}
+
+/// 把已解码的一组图片纵向拼接为一张画布并写入磁盘，返回写入的文件路径
+fn flush_long_strip_chunk(
+    rows: &[image::RgbaImage],
+    width: u32,
+    height: u32,
+    output_dir: &Path,
+    stem: &str,
+    chunk_index: u32,
+    format: LongStripFormat,
+) -> anyhow::Result<PathBuf> {
+    let mut canvas = image::RgbaImage::new(width, height);
+    let mut y: i64 = 0;
+    for row in rows {
+        image::imageops::overlay(&mut canvas, row, 0, y);
+        y += i64::from(row.height());
+    }
+
+    let ext = match format {
+        LongStripFormat::Png => "png",
+        LongStripFormat::WebP => "webp",
+    };
+    let output_path = output_dir.join(format!("{stem}_{chunk_index:03}.{ext}"));
+    let image_format = match format {
+        LongStripFormat::Png => image::ImageFormat::Png,
+        LongStripFormat::WebP => image::ImageFormat::WebP,
+    };
+    image::DynamicImage::ImageRgba8(canvas)
+        .save_with_format(&output_path, image_format)
+        .with_context(|| format!("保存长图`{output_path:?}`失败"))?;
+
+    Ok(output_path)
+}
+
+/// 根据冲突策略，计算导出文件最终应该写入的路径
+///
+/// 如果目标路径不存在冲突，直接返回该路径。
+/// 如果策略为`Skip`且目标路径已存在，返回`None`，调用方应跳过此次导出并上报事件。
+pub fn resolve_output_path(path: &Path, policy: ExportConflictPolicy) -> Option<PathBuf> {
+    if !path.exists() {
+        return Some(path.to_path_buf());
+    }
+
+    match policy {
+        ExportConflictPolicy::Overwrite => Some(path.to_path_buf()),
+        ExportConflictPolicy::Skip => None,
+        ExportConflictPolicy::Rename => {
+            let stem = path.file_stem()?.to_string_lossy().to_string();
+            let ext = path.extension().map(|e| e.to_string_lossy().to_string());
+            let parent = path.parent()?;
+            let mut i = 1;
+            loop {
+                let candidate_name = match &ext {
+                    Some(ext) => format!("{stem} ({i}).{ext}"),
+                    None => format!("{stem} ({i})"),
+                };
+                let candidate = parent.join(candidate_name);
+                if !candidate.exists() {
+                    return Some(candidate);
+                }
+                i += 1;
+            }
+        }
+    }
+}
+
+/// 从漫画标题中拆分出原名与译名：哔咔上很多本子的标题形如`中文译名（日文原名）`，
+/// 返回`(去掉括号部分的标题, 括号内的标题)`；没有括号时后者为`None`，只取最后一对括号，
+/// 同时兼容半角`()`和全角`（）`
+pub fn parse_bilingual_title(title: &str) -> (String, Option<String>) {
+    let title = title.trim();
+    for (open, close) in [('(', ')'), ('（', '）')] {
+        if let (Some(open_idx), Some(close_idx)) = (title.rfind(open), title.rfind(close)) {
+            if open_idx < close_idx {
+                let localized = title[open_idx + open.len_utf8()..close_idx].trim().to_string();
+                let primary = format!(
+                    "{}{}",
+                    &title[..open_idx],
+                    &title[close_idx + close.len_utf8()..]
+                );
+                let primary = primary.trim().to_string();
+                if !localized.is_empty() && !primary.is_empty() {
+                    return (primary, Some(localized));
+                }
+            }
+        }
+    }
+    (title.to_string(), None)
+}
+
+/// 生成写入CBZ根目录的`ComicInfo.xml`，遵循Komga/Kavita等阅读器通用的ComicRack格式：
+/// `Series`为主标题，`LocalizedSeries`为[`parse_bilingual_title`]解析出的括号内译名/原名(没有则省略)，
+/// `Number`为章节序号(`Episode.order`)，`ScanInformation`记录汉化组(为空则省略)，
+/// `Tags`为逗号分隔的标签列表(为空则省略)，`PageCount`为压缩包内实际打包的图片数量，
+/// `Manga`固定为`YesAndRightToLeft`，因为哔咔漫画均为日式从右到左的阅读顺序
+#[allow(clippy::too_many_arguments)]
+pub fn build_comic_info_xml(
+    comic_title: &str,
+    ep_title: &str,
+    author: &str,
+    chinese_team: &str,
+    tags: &[String],
+    chapter_number: i64,
+    page_count: usize,
+) -> String {
+    let (series, localized_series) = parse_bilingual_title(comic_title);
+    let mut xml = String::new();
+    xml.push_str("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n");
+    xml.push_str("<ComicInfo xmlns:xsi=\"http://www.w3.org/2001/XMLSchema-instance\" xmlns:xsd=\"http://www.w3.org/2001/XMLSchema\">\n");
+    xml.push_str(&format!("  <Series>{}</Series>\n", xml_escape(&series)));
+    if let Some(localized_series) = &localized_series {
+        xml.push_str(&format!(
+            "  <LocalizedSeries>{}</LocalizedSeries>\n",
+            xml_escape(localized_series)
+        ));
+    }
+    xml.push_str(&format!("  <Title>{}</Title>\n", xml_escape(ep_title)));
+    xml.push_str(&format!("  <Number>{chapter_number}</Number>\n"));
+    xml.push_str(&format!("  <Writer>{}</Writer>\n", xml_escape(author)));
+    if !chinese_team.is_empty() {
+        xml.push_str(&format!(
+            "  <ScanInformation>{}</ScanInformation>\n",
+            xml_escape(chinese_team)
+        ));
+    }
+    if !tags.is_empty() {
+        xml.push_str(&format!("  <Tags>{}</Tags>\n", xml_escape(&tags.join(", "))));
+    }
+    xml.push_str(&format!("  <PageCount>{page_count}</PageCount>\n"));
+    xml.push_str("  <Manga>YesAndRightToLeft</Manga>\n");
+    xml.push_str("</ComicInfo>\n");
+    xml
+}
+
+fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+/// 校验刚导出的CBZ文件是否完好：能被正常打开为zip，且entry数量与`expected_entry_count`一致
+///
+/// 磁盘写满等情况可能导致导出的文件已损坏，此校验用于在导出完成后尽早发现这类问题
+pub fn verify_cbz(path: &Path, expected_entry_count: usize) -> anyhow::Result<()> {
+    let file = std::fs::File::open(path).with_context(|| format!("打开`{path:?}`失败"))?;
+    let mut archive = zip::ZipArchive::new(file)
+        .map_err(|e| anyhow::anyhow!("`{path:?}`不是有效的zip文件: {e}"))?;
+    if archive.len() != expected_entry_count {
+        return Err(anyhow::anyhow!(
+            "`{path:?}`应包含`{expected_entry_count}`个文件，实际只有`{}`个",
+            archive.len()
+        ));
+    }
+    for i in 0..archive.len() {
+        archive
+            .by_index(i)
+            .map_err(|e| anyhow::anyhow!("`{path:?}`的第`{i}`个entry已损坏: {e}"))?;
+    }
+    Ok(())
+}
+
+/// 校验刚导出的PDF文件是否完好：能被正常解析，且页数与`expected_page_count`一致
+///
+/// 磁盘写满等情况可能导致导出的文件已损坏，此校验用于在导出完成后尽早发现这类问题
+pub fn verify_pdf(path: &Path, expected_page_count: usize) -> anyhow::Result<()> {
+    let doc = printpdf::lopdf::Document::load(path)
+        .map_err(|e| anyhow::anyhow!("`{path:?}`不是有效的PDF文件: {e}"))?;
+    let page_count = doc.get_pages().len();
+    if page_count != expected_page_count {
+        return Err(anyhow::anyhow!(
+            "`{path:?}`应有`{expected_page_count}`页，实际只有`{page_count}`页"
+        ));
+    }
+    Ok(())
+}
+
+/// 一个待打包进EPUB的章节：`title`作为该章节在目录(spine/导航)中显示的名字，
+/// `image_paths`为该章节下按阅读顺序排列的图片路径
+pub struct EpubChapter {
+    pub title: String,
+    pub image_paths: Vec<PathBuf>,
+}
+
+/// 写入EPUB的`dc:`元数据
+pub struct EpubMetadata {
+    pub title: String,
+    pub author: String,
+    pub tags: Vec<String>,
+}
+
+/// 把多个章节打包为一本EPUB电子书：每个章节对应一个spine条目(一个内嵌全部图片的xhtml页面)，
+/// 目录(`toc.ncx`)中每章一条导航项，方便在电子书阅读器的目录面板快速跳转
+///
+/// 生成的是EPUB 2.0，`content.opf`中不声明固定版式(Fixed Layout)元数据，每章的图片在同一个xhtml页面中依次排列
+pub fn chapters_to_epub(chapters: &[EpubChapter], metadata: &EpubMetadata) -> anyhow::Result<Vec<u8>> {
+    let uid = format!("picacomic-downloader-{}", fnv1a_hex(&metadata.title));
+
+    let mut buffer = std::io::Cursor::new(vec![]);
+    let mut zip = zip::ZipWriter::new(&mut buffer);
+    let stored =
+        zip::write::SimpleFileOptions::default().compression_method(zip::CompressionMethod::Stored);
+    let deflated = zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated);
+
+    // mimetype必须是zip包的第一个entry，且不能被压缩，这是EPUB规范的硬性要求
+    zip.start_file("mimetype", stored)
+        .map_err(|e| anyhow::anyhow!("写入mimetype失败: {e}"))?;
+    std::io::Write::write_all(&mut zip, b"application/epub+zip")
+        .map_err(|e| anyhow::anyhow!("写入mimetype失败: {e}"))?;
+
+    zip.start_file("META-INF/container.xml", deflated)
+        .map_err(|e| anyhow::anyhow!("写入container.xml失败: {e}"))?;
+    std::io::Write::write_all(&mut zip, CONTAINER_XML.as_bytes())
+        .map_err(|e| anyhow::anyhow!("写入container.xml失败: {e}"))?;
+
+    let mut manifest_items = String::new();
+    let mut spine_items = String::new();
+    let mut nav_points = String::new();
+    for (chapter_index, chapter) in chapters.iter().enumerate() {
+        let chapter_id = format!("chap{chapter_index}");
+        let mut body = String::new();
+        for (image_index, image_path) in chapter.image_paths.iter().enumerate() {
+            let ext = image_path
+                .extension()
+                .map(|e| e.to_string_lossy().to_lowercase())
+                .unwrap_or_else(|| "jpg".to_string());
+            let Some(media_type) = image_media_type(&ext) else {
+                continue;
+            };
+            let data =
+                std::fs::read(image_path).map_err(|e| anyhow::anyhow!("读取图片`{image_path:?}`失败: {e}"))?;
+            let image_name = format!("img_{chapter_index}_{image_index}.{ext}");
+            zip.start_file(format!("OEBPS/images/{image_name}"), stored)
+                .map_err(|e| anyhow::anyhow!("写入`{image_name}`失败: {e}"))?;
+            std::io::Write::write_all(&mut zip, &data)
+                .map_err(|e| anyhow::anyhow!("写入`{image_name}`失败: {e}"))?;
+
+            let image_id = format!("img_{chapter_index}_{image_index}");
+            manifest_items.push_str(&format!(
+                "    <item id=\"{image_id}\" href=\"images/{image_name}\" media-type=\"{media_type}\"/>\n"
+            ));
+            body.push_str(&format!(
+                "  <img src=\"images/{image_name}\" alt=\"\"/>\n"
+            ));
+        }
+
+        let chapter_title = xml_escape(&chapter.title);
+        let chapter_xhtml = format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+<!DOCTYPE html>\n\
+<html xmlns=\"http://www.w3.org/1999/xhtml\">\n\
+<head><title>{chapter_title}</title></head>\n\
+<body>\n{body}</body>\n\
+</html>\n"
+        );
+        let chapter_file = format!("{chapter_id}.xhtml");
+        zip.start_file(format!("OEBPS/{chapter_file}"), deflated)
+            .map_err(|e| anyhow::anyhow!("写入`{chapter_file}`失败: {e}"))?;
+        std::io::Write::write_all(&mut zip, chapter_xhtml.as_bytes())
+            .map_err(|e| anyhow::anyhow!("写入`{chapter_file}`失败: {e}"))?;
+
+        manifest_items.push_str(&format!(
+            "    <item id=\"{chapter_id}\" href=\"{chapter_file}\" media-type=\"application/xhtml+xml\"/>\n"
+        ));
+        spine_items.push_str(&format!("    <itemref idref=\"{chapter_id}\"/>\n"));
+        nav_points.push_str(&format!(
+            "    <navPoint id=\"nav-{chapter_id}\" playOrder=\"{}\">\n      <navLabel><text>{chapter_title}</text></navLabel>\n      <content src=\"{chapter_file}\"/>\n    </navPoint>\n",
+            chapter_index + 1
+        ));
+    }
+
+    let subjects: String = metadata
+        .tags
+        .iter()
+        .map(|tag| format!("    <dc:subject>{}</dc:subject>\n", xml_escape(tag)))
+        .collect();
+    let content_opf = format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+<package xmlns=\"http://www.idpf.org/2007/opf\" unique-identifier=\"BookId\" version=\"2.0\">\n\
+  <metadata xmlns:dc=\"http://purl.org/dc/elements/1.1/\">\n\
+    <dc:title>{}</dc:title>\n\
+    <dc:creator>{}</dc:creator>\n\
+    <dc:language>zh</dc:language>\n\
+    <dc:identifier id=\"BookId\">urn:uuid:{uid}</dc:identifier>\n\
+{subjects}  </metadata>\n\
+  <manifest>\n\
+    <item id=\"ncx\" href=\"toc.ncx\" media-type=\"application/x-dtbncx+xml\"/>\n\
+{manifest_items}  </manifest>\n\
+  <spine toc=\"ncx\">\n\
+{spine_items}  </spine>\n\
+</package>\n",
+        xml_escape(&metadata.title),
+        xml_escape(&metadata.author),
+    );
+    zip.start_file("OEBPS/content.opf", deflated)
+        .map_err(|e| anyhow::anyhow!("写入content.opf失败: {e}"))?;
+    std::io::Write::write_all(&mut zip, content_opf.as_bytes())
+        .map_err(|e| anyhow::anyhow!("写入content.opf失败: {e}"))?;
+
+    let toc_ncx = format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+<ncx xmlns=\"http://www.daisy.org/z3986/2005/ncx/\" version=\"2005-1\">\n\
+  <head>\n    <meta name=\"dtb:uid\" content=\"urn:uuid:{uid}\"/>\n  </head>\n\
+  <docTitle><text>{}</text></docTitle>\n\
+  <navMap>\n{nav_points}  </navMap>\n\
+</ncx>\n",
+        xml_escape(&metadata.title),
+    );
+    zip.start_file("OEBPS/toc.ncx", deflated)
+        .map_err(|e| anyhow::anyhow!("写入toc.ncx失败: {e}"))?;
+    std::io::Write::write_all(&mut zip, toc_ncx.as_bytes())
+        .map_err(|e| anyhow::anyhow!("写入toc.ncx失败: {e}"))?;
+
+    zip.finish()
+        .map_err(|e| anyhow::anyhow!("完成EPUB的写入失败: {e}"))?;
+    drop(zip);
+    Ok(buffer.into_inner())
+}
+
+const CONTAINER_XML: &str = "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+<container version=\"1.0\" xmlns=\"urn:oasis:names:tc:opendocument:xmlns:container\">\n\
+  <rootfiles>\n    <rootfile full-path=\"OEBPS/content.opf\" media-type=\"application/oebps-package+xml\"/>\n  </rootfiles>\n\
+</container>\n";
+
+/// 图片扩展名到EPUB manifest所需media-type的映射，不认识的扩展名返回`None`并跳过该图片
+fn image_media_type(ext: &str) -> Option<&'static str> {
+    match ext {
+        "jpg" | "jpeg" => Some("image/jpeg"),
+        "png" => Some("image/png"),
+        "webp" => Some("image/webp"),
+        "gif" => Some("image/gif"),
+        _ => None,
+    }
+}
+
+/// 用FNV-1a哈希漫画标题，生成一个稳定的十六进制短字符串，拼进`urn:uuid:`充当EPUB的唯一标识符，
+/// 避免引入专门的uuid依赖
+fn fnv1a_hex(s: &str) -> String {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in s.as_bytes() {
+        hash ^= u64::from(*byte);
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    format!("{hash:016x}")
+}
+
+/// 校验刚导出的整本漫画ZIP是否完好：能被正常打开为zip，且entry数量与`expected_entry_count`一致
+///
+/// 磁盘写满等情况可能导致导出的文件已损坏，此校验用于在导出完成后尽早发现这类问题
+pub fn verify_zip(path: &Path, expected_entry_count: usize) -> anyhow::Result<()> {
+    let file = std::fs::File::open(path).with_context(|| format!("打开`{path:?}`失败"))?;
+    let mut archive = zip::ZipArchive::new(file)
+        .map_err(|e| anyhow::anyhow!("`{path:?}`不是有效的zip文件: {e}"))?;
+    if archive.len() != expected_entry_count {
+        return Err(anyhow::anyhow!(
+            "`{path:?}`应包含`{expected_entry_count}`个文件，实际只有`{}`个",
+            archive.len()
+        ));
+    }
+    for i in 0..archive.len() {
+        archive
+            .by_index(i)
+            .map_err(|e| anyhow::anyhow!("`{path:?}`的第`{i}`个entry已损坏: {e}"))?;
+    }
+    Ok(())
+}
+
+/// 校验刚导出的EPUB文件是否完好：能被正常打开为zip，且章节xhtml数量与`expected_chapter_count`一致
+///
+/// 磁盘写满等情况可能导致导出的文件已损坏，此校验用于在导出完成后尽早发现这类问题
+pub fn verify_epub(path: &Path, expected_chapter_count: usize) -> anyhow::Result<()> {
+    let file = std::fs::File::open(path).with_context(|| format!("打开`{path:?}`失败"))?;
+    let mut archive = zip::ZipArchive::new(file)
+        .map_err(|e| anyhow::anyhow!("`{path:?}`不是有效的zip文件: {e}"))?;
+    let chapter_count = (0..archive.len())
+        .filter(|&i| {
+            archive
+                .by_index(i)
+                .is_ok_and(|entry| entry.name().starts_with("OEBPS/chap") && entry.name().ends_with(".xhtml"))
+        })
+        .count();
+    if chapter_count != expected_chapter_count {
+        return Err(anyhow::anyhow!(
+            "`{path:?}`应包含`{expected_chapter_count}`个章节，实际只有`{chapter_count}`个"
+        ));
+    }
+    Ok(())
+}
@@ -0,0 +1,51 @@
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+/// "下载全部收藏"任务中尚未处理完的漫画id队列，随每次处理完一本漫画实时持久化到磁盘；
+/// 应用在任务中途被关闭或任务失败退出时，下次调用[`crate::commands::download_all_favorites`]
+/// 会从队列中剩余的漫画继续，而不必从头重新下载整个收藏夹
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FavoritesDownloadQueueStore {
+    comic_ids: Vec<String>,
+}
+
+impl FavoritesDownloadQueueStore {
+    pub fn new(app: &AppHandle) -> anyhow::Result<Self> {
+        let path = Self::path(app)?;
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let string = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&string).unwrap_or_default())
+    }
+
+    pub fn comic_ids(&self) -> Vec<String> {
+        self.comic_ids.clone()
+    }
+
+    pub fn replace(&mut self, app: &AppHandle, comic_ids: Vec<String>) -> anyhow::Result<()> {
+        self.comic_ids = comic_ids;
+        self.save(app)
+    }
+
+    pub fn remove(&mut self, app: &AppHandle, comic_id: &str) -> anyhow::Result<()> {
+        self.comic_ids.retain(|id| id != comic_id);
+        self.save(app)
+    }
+
+    fn save(&self, app: &AppHandle) -> anyhow::Result<()> {
+        let path = Self::path(app)?;
+        let string = serde_json::to_string_pretty(self)?;
+        std::fs::write(path, string)?;
+        Ok(())
+    }
+
+    fn path(app: &AppHandle) -> anyhow::Result<std::path::PathBuf> {
+        let app_data_dir = app
+            .path()
+            .app_data_dir()
+            .context("failed to get app data dir")?;
+        Ok(app_data_dir.join("favorites_download_queue.json"))
+    }
+}
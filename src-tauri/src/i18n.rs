@@ -0,0 +1,38 @@
+use crate::types::Language;
+
+/// 面向用户的错误标题/事件文案的中英文对照表；目前只收录了少数跨多处复用的通用文案，
+/// 仓库里绝大多数`anyhow!`错误消息仍是内联的中文硬编码，尚未逐条迁移到这里
+const MESSAGES: &[(&str, &str, &str)] = &[
+    ("guest_mode_disabled", "访客模式已禁用该操作", "This action is disabled in guest mode"),
+    (
+        "offline_mode_disabled",
+        "离线模式下已禁用该操作，仅能浏览本地已下载内容",
+        "This action is disabled in offline mode; only locally downloaded content can be browsed",
+    ),
+    (
+        "download_dir_not_exist",
+        "下载目录`{dir}`不存在",
+        "Download directory `{dir}` does not exist",
+    ),
+    (
+        "download_dir_not_writable",
+        "下载目录`{dir}`不可写: {err}",
+        "Download directory `{dir}` is not writable: {err}",
+    ),
+];
+
+/// 按`language`返回`key`对应的文案，用`{name}`占位符插入`args`中的值；
+/// `key`不存在时原样返回`key`本身，避免因为拼写错误而直接panic。
+/// 不直接依赖`AppHandle`，调用方自行从`Config.language`取值传入，保持这个函数易于单独调用
+pub fn t(language: Language, key: &str, args: &[(&str, &str)]) -> String {
+    let Some(&(_, zh, en)) = MESSAGES.iter().find(|(k, _, _)| *k == key) else {
+        return key.to_string();
+    };
+    let template = match language {
+        Language::Zh => zh,
+        Language::En => en,
+    };
+    args.iter().fold(template.to_string(), |message, (name, value)| {
+        message.replace(&format!("{{{name}}}"), value)
+    })
+}
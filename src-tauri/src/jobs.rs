@@ -0,0 +1,46 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use crate::extensions::IgnoreLockPoison;
+
+/// 所有长任务（下载、导出、批量遍历等）共用的取消令牌注册表，按`job_id`注册/查询/取消
+///
+/// 长任务开始时调用`register`换取一个取消令牌，并在关键的await点/循环迭代处检查该令牌，
+/// 发现已取消则尽快结束当前任务；任务结束后调用`finish`清理注册表，避免无限增长。
+/// 已经开始执行的工作（例如已派发给线程池/已发出的网络请求）不会被中途打断，
+/// 取消只影响尚未开始的后续步骤
+///
+/// 目前已接入该注册表的长任务：章节下载(`job_id`为`ep_id`)、转码(`job_id`为`comic_title`)
+#[derive(Default)]
+pub struct JobRegistry(Mutex<HashMap<String, Arc<AtomicBool>>>);
+impl JobRegistry {
+    /// 为`job_id`注册一个新的取消令牌，若已存在旧令牌则直接覆盖
+    pub fn register(&self, job_id: &str) -> Arc<AtomicBool> {
+        let token = Arc::new(AtomicBool::new(false));
+        self.0
+            .lock_or_panic()
+            .insert(job_id.to_string(), token.clone());
+        token
+    }
+
+    /// 任务结束后移除`job_id`对应的取消令牌
+    pub fn finish(&self, job_id: &str) {
+        self.0.lock_or_panic().remove(job_id);
+    }
+
+    /// 请求取消`job_id`对应的任务，返回该任务是否存在（已结束或不存在的任务返回`false`）
+    pub fn cancel(&self, job_id: &str) -> bool {
+        if let Some(token) = self.0.lock_or_panic().get(job_id) {
+            token.store(true, Ordering::Relaxed);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// 判断`job_id`对应的任务当前是否正在进行中，用于其他操作（如导出）检测冲突后拒绝/跳过
+    pub fn is_active(&self, job_id: &str) -> bool {
+        self.0.lock_or_panic().contains_key(job_id)
+    }
+}
@@ -1,23 +1,64 @@
+use std::sync::Arc;
+
 use anyhow::Context;
 use tauri::{Manager, Wry};
 
 // TODO: 使用 prelude 来消除警告
+use crate::comic_tasks::ComicTaskRegistry;
 use crate::commands::*;
-use crate::config::Config;
+use crate::config::{Config, ConfigChangeNotifier};
+use crate::content_index::ContentIndex;
 use crate::download_manager::DownloadManager;
+use crate::download_quota::DownloadQuotaStore;
 use crate::events::prelude::*;
+use crate::extensions::IgnoreRwLockPoison;
+use crate::favorites_download_queue::FavoritesDownloadQueueStore;
+use crate::jobs::JobRegistry;
+use crate::library_index::LibraryIndex;
+use crate::log::FrontendLogState;
+use crate::opds::OpdsHandle;
+use crate::pending_downloads::PendingDownloadsStore;
+use crate::pica_api::PicaApi;
+#[cfg(feature = "mock-pica")]
+use crate::pica_client_mock::MockPicaClient;
 use crate::pica_client::PicaClient;
+use crate::reading_progress::ReadingProgressStore;
+use crate::share::ShareState;
+use crate::tag_subscriptions::TagSubscriptionStore;
+use crate::wishlist::Wishlist;
 
+mod clipboard_watcher;
+mod comic_tasks;
 mod commands;
 mod config;
+mod content_index;
 mod download_manager;
+mod download_quota;
+mod enums_meta;
 mod errors;
 mod events;
+mod export;
 mod extensions;
+mod favorites_download_queue;
+mod i18n;
+mod jobs;
+mod library_index;
+mod log;
+mod opds;
+mod pending_downloads;
+mod pica_api;
 mod pica_client;
+#[cfg(feature = "mock-pica")]
+mod pica_client_mock;
+mod reading_progress;
 mod responses;
+mod share;
+mod tag_subscriptions;
+mod thumbnails;
+mod transcode;
 mod types;
 mod utils;
+mod wishlist;
 
 fn generate_context() -> tauri::Context<Wry> {
     tauri::generate_context!()
@@ -41,6 +82,77 @@ pub async fn run() {
             download_comic,
             show_path_in_file_manager,
             get_favorite_comics,
+            analyze_disk_usage,
+            garbage_collect_library,
+            health_check,
+            export_episode_as_cbz,
+            export_episode_as_pdf,
+            open_with_default_app,
+            get_downloaded_comics,
+            add_to_wishlist,
+            remove_from_wishlist,
+            get_wishlist,
+            preview_download_comic,
+            debug_download_image,
+            download_all_favorites,
+            upscale_chapter,
+            get_reading_progress,
+            set_reading_progress,
+            start_opds_server,
+            stop_opds_server,
+            share_comic,
+            stop_share_comic,
+            get_local_comic_meta,
+            set_local_tags,
+            set_rating,
+            get_comics_by_local_tag,
+            get_downloaded_comics_filtered,
+            merge_comic_versions,
+            get_comic_download_progress,
+            export_episodes_as_cbz,
+            export_episodes_as_pdf,
+            export_episode_as_long_strip,
+            export_comic_as_epub,
+            export_zip,
+            migrate_filenames,
+            get_announcements,
+            transcode_downloaded,
+            cancel_job,
+            auto_archive,
+            export_library_manifest,
+            diff_with_manifest,
+            coverage_report,
+            get_categories,
+            get_comments,
+            like_comic,
+            test_channels,
+            get_enums_meta,
+            get_comics_by_source,
+            sync_library_to_favorites,
+            get_speed_history,
+            migrate_library_index_filename,
+            set_frontend_log_level,
+            export_pending_downloads,
+            import_pending_downloads,
+            get_app_info,
+            collect_debug_bundle,
+            analyze_image_formats,
+            search_comic_grouped,
+            compare_comics,
+            replace_chapter_page,
+            add_tag_subscription,
+            remove_tag_subscription,
+            get_tag_subscriptions,
+            check_tag_subscriptions,
+            get_circuit_breaker_status,
+            resume_download_circuit_breaker,
+            cancel_auto_power_action,
+            get_download_quota_status,
+            get_chapter_thumbnails,
+            create_comic_download_task,
+            pause_comic_download_task,
+            resume_comic_download_task,
+            cancel_comic_download_task,
         ])
         .events(tauri_specta::collect_events![
             DownloadEpisodeEndEvent,
@@ -49,7 +161,22 @@ pub async fn run() {
             DownloadImageErrorEvent,
             DownloadImageSuccessEvent,
             DownloadSpeedEvent,
-            UpdateOverallDownloadProgressEvent
+            UpdateOverallDownloadProgressEvent,
+            HealthCheckProgressEvent,
+            ExportFileSkippedEvent,
+            DownloadPausedEvent,
+            ComicDownloadProgressEvent,
+            ExportLongStripProgressEvent,
+            ExportZipProgressEvent,
+            AutoPowerCountdownEvent,
+            TranscodeProgressEvent,
+            ArchiveVolumeCreatedEvent,
+            LogEvent,
+            DownloadDirUnwritableEvent,
+            NewTagComicFoundEvent,
+            DownloadWaitEvent,
+            LibraryDirSwitchedEvent,
+            ClipboardComicFoundEvent
         ]);
 
     #[cfg(debug_assertions)]
@@ -68,6 +195,19 @@ pub async fn run() {
         .plugin(tauri_plugin_fs::init())
         .plugin(tauri_plugin_shell::init())
         .invoke_handler(builder.invoke_handler())
+        .on_window_event(|window, event| {
+            // 优雅停机：拦截关闭请求，暂停下载并等待正在写盘的图片完成后再真正退出
+            let tauri::WindowEvent::CloseRequested { api, .. } = event else {
+                return;
+            };
+            api.prevent_close();
+            let app = window.app_handle().clone();
+            tauri::async_runtime::spawn(async move {
+                let download_manager = app.state::<DownloadManager>().inner().clone();
+                download_manager.prepare_shutdown().await;
+                app.exit(0);
+            });
+        })
         .setup(move |app| {
             builder.mount_events(app);
 
@@ -81,12 +221,66 @@ pub async fn run() {
             println!("app data dir: {app_data_dir:?}");
 
             let config = std::sync::RwLock::new(Config::new(app.handle())?);
-            let pica_client = PicaClient::new(app.handle().clone());
+            // mock-pica特性开启时，用读取本地夹具的MockPicaClient代替真实的PicaClient，
+            // 便于在没有哔咔账号/网络的情况下离线开发前端、编写下载状态机的端到端测试
+            #[cfg(feature = "mock-pica")]
+            let pica_client: Arc<dyn PicaApi> = Arc::new(MockPicaClient::new());
+            #[cfg(not(feature = "mock-pica"))]
+            let pica_client: Arc<dyn PicaApi> = Arc::new(PicaClient::new(app.handle().clone()));
             let download_manager = DownloadManager::new(app.handle().clone());
+            let wishlist = std::sync::RwLock::new(Wishlist::new(app.handle())?);
+            let reading_progress = std::sync::RwLock::new(ReadingProgressStore::new(app.handle())?);
+            let library_index_filename = config.read_or_panic().library_index_filename.clone();
+            let library_index = std::sync::RwLock::new(LibraryIndex::new(
+                app.handle(),
+                &library_index_filename,
+            )?);
+            let pending_downloads = PendingDownloadsStore::new(app.handle())?;
+            let resume_episodes = pending_downloads.episodes();
+            let resume_manager = download_manager.clone();
+            let tag_subscriptions = std::sync::RwLock::new(TagSubscriptionStore::new(app.handle())?);
+            let download_quota = std::sync::RwLock::new(DownloadQuotaStore::new(app.handle())?);
+            let favorites_download_queue =
+                std::sync::RwLock::new(FavoritesDownloadQueueStore::new(app.handle())?);
+            let content_index = std::sync::RwLock::new(ContentIndex::new(app.handle())?);
 
             app.manage(config);
             app.manage(pica_client);
             app.manage(download_manager);
+            app.manage(wishlist);
+            app.manage(reading_progress);
+            app.manage(library_index);
+            app.manage(OpdsHandle::default());
+            app.manage(ShareState::default());
+            app.manage(JobRegistry::default());
+            app.manage(ComicTaskRegistry::default());
+            app.manage(std::sync::RwLock::new(pending_downloads));
+            app.manage(FrontendLogState::default());
+            app.manage(crate::events::EmitFailureStats::default());
+            app.manage(ConfigChangeNotifier::default());
+            app.manage(tag_subscriptions);
+            app.manage(download_quota);
+            app.manage(favorites_download_queue);
+            app.manage(content_index);
+
+            // 恢复上次退出时尚未完成的章节下载
+            tauri::async_runtime::spawn(async move {
+                for ep in resume_episodes {
+                    let _ = resume_manager.submit_episode(ep).await;
+                }
+            });
+
+            // 定期检查tag订阅，发现新作时提醒并按配置自动下载首话试读
+            let tag_subscription_app = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                crate::commands::run_tag_subscription_scheduler(tag_subscription_app).await;
+            });
+
+            // 轮询剪贴板，开启`clipboard_watcher_enabled`后检测到漫画id/链接就提醒前端
+            let clipboard_watcher_app = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                crate::clipboard_watcher::run_clipboard_watcher(clipboard_watcher_app).await;
+            });
 
             Ok(())
         })
@@ -0,0 +1,175 @@
+use std::path::PathBuf;
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use tauri::{AppHandle, Manager};
+
+use crate::config::Config;
+use crate::extensions::IgnoreRwLockPoison;
+
+/// `library_index_filename`配置项引入前，该文件固定使用的文件名，仍作为迁移时的"旧文件名"
+const DEFAULT_FILENAME: &str = "library_index.json";
+
+/// 某本已下载漫画的本地标签与评分，与哔咔自带的标签/评分相互独立
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalComicMeta {
+    pub comic_title: String,
+    pub tags: Vec<String>,
+    pub rating: Option<u8>,
+    /// 下载来源上下文，例如`search:关键词`、`rank:day`、`favorite`，用于回忆某本漫画是怎么找到的
+    pub source: Option<String>,
+    /// 任务级别的保存目录，覆盖全局`download_dir`，见[`crate::commands::download_comic`]
+    pub target_dir: Option<PathBuf>,
+    /// 该漫画在哔咔上的完结状态，每次`download_comic`时从线上数据同步；`None`表示尚未同步过(从未下载过)
+    #[serde(default)]
+    pub finished: Option<bool>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LibraryIndex {
+    comics: Vec<LocalComicMeta>,
+}
+
+impl LibraryIndex {
+    /// `filename`由调用方传入而非直接从`Config`读取：此方法在应用启动阶段调用，
+    /// 此时`Config`尚未被`app.manage`托管，无法通过`app.state::<RwLock<Config>>()`访问
+    pub fn new(app: &AppHandle, filename: &str) -> anyhow::Result<Self> {
+        let path = Self::resolve_path(app, filename)?;
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&content).unwrap_or_default())
+    }
+
+    pub fn get(&self, comic_title: &str) -> LocalComicMeta {
+        self.comics
+            .iter()
+            .find(|meta| meta.comic_title == comic_title)
+            .cloned()
+            .unwrap_or_else(|| LocalComicMeta {
+                comic_title: comic_title.to_string(),
+                ..Default::default()
+            })
+    }
+
+    pub fn set_tags(&mut self, comic_title: &str, tags: Vec<String>) {
+        self.entry(comic_title).tags = tags;
+    }
+
+    /// 为某本漫画追加一个本地标签，已存在则不重复添加；与`set_tags`整体覆盖不同
+    pub fn add_tag(&mut self, comic_title: &str, tag: &str) {
+        let meta = self.entry(comic_title);
+        if !meta.tags.iter().any(|t| t == tag) {
+            meta.tags.push(tag.to_string());
+        }
+    }
+
+    pub fn set_rating(&mut self, comic_title: &str, rating: Option<u8>) {
+        self.entry(comic_title).rating = rating;
+    }
+
+    /// 记录某本漫画的下载来源上下文，已存在来源时直接覆盖为最新一次的下载来源
+    pub fn set_source(&mut self, comic_title: &str, source: Option<String>) {
+        self.entry(comic_title).source = source;
+    }
+
+    /// 记录某本漫画的任务级别保存目录，已存在时直接覆盖为最新一次指定的目录
+    pub fn set_target_dir(&mut self, comic_title: &str, target_dir: Option<PathBuf>) {
+        self.entry(comic_title).target_dir = target_dir;
+    }
+
+    /// 记录某本漫画的完结状态，每次下载时从线上数据同步覆盖
+    pub fn set_finished(&mut self, comic_title: &str, finished: bool) {
+        self.entry(comic_title).finished = Some(finished);
+    }
+
+    /// 返回所有带有指定本地标签的漫画标题
+    pub fn filter_by_tag(&self, tag: &str) -> Vec<String> {
+        self.comics
+            .iter()
+            .filter(|meta| meta.tags.iter().any(|t| t == tag))
+            .map(|meta| meta.comic_title.clone())
+            .collect()
+    }
+
+    /// 返回下载来源以`category`开头的漫画标题，例如传入`search`可匹配`search:关键词`
+    pub fn filter_by_source(&self, category: &str) -> Vec<String> {
+        self.comics
+            .iter()
+            .filter(|meta| {
+                meta.source
+                    .as_ref()
+                    .is_some_and(|source| source.starts_with(category))
+            })
+            .map(|meta| meta.comic_title.clone())
+            .collect()
+    }
+
+    /// 返回完结状态与`finished`一致的漫画标题；从未同步过完结状态的漫画(`finished`字段为`None`)不会被任何取值匹配到
+    pub fn filter_by_finished(&self, finished: bool) -> Vec<String> {
+        self.comics
+            .iter()
+            .filter(|meta| meta.finished == Some(finished))
+            .map(|meta| meta.comic_title.clone())
+            .collect()
+    }
+
+    fn entry(&mut self, comic_title: &str) -> &mut LocalComicMeta {
+        if let Some(index) = self
+            .comics
+            .iter()
+            .position(|meta| meta.comic_title == comic_title)
+        {
+            return &mut self.comics[index];
+        }
+        self.comics.push(LocalComicMeta {
+            comic_title: comic_title.to_string(),
+            ..Default::default()
+        });
+        self.comics
+            .last_mut()
+            .expect("刚刚push进去的元素必然存在")
+    }
+
+    pub fn save(&self, app: &AppHandle) -> anyhow::Result<()> {
+        let filename = app
+            .state::<std::sync::RwLock<Config>>()
+            .read_or_panic()
+            .library_index_filename
+            .clone();
+        let path = Self::resolve_path(app, &filename)?;
+        let content = serde_json::to_string_pretty(self)?;
+        std::fs::write(path, content)?;
+        Ok(())
+    }
+
+    fn resolve_path(app: &AppHandle, filename: &str) -> anyhow::Result<PathBuf> {
+        let app_data_dir = app
+            .path()
+            .app_data_dir()
+            .context("failed to get app data dir")?;
+        Ok(app_data_dir.join(filename))
+    }
+
+    /// 将`filename`配置变更前、使用旧默认文件名`library_index.json`保存的文件迁移为`new_filename`，
+    /// 用于`library_index_filename`配置从默认值修改后的一次性迁移
+    pub fn migrate_filename(app: &AppHandle, new_filename: &str) -> anyhow::Result<String> {
+        if new_filename == DEFAULT_FILENAME {
+            return Ok(format!("`{new_filename}`与默认文件名相同，无需迁移"));
+        }
+        let old_path = Self::resolve_path(app, DEFAULT_FILENAME)?;
+        let new_path = Self::resolve_path(app, new_filename)?;
+        if !old_path.exists() {
+            return Ok(format!("`{old_path:?}`不存在，无需迁移"));
+        }
+        if new_path.exists() {
+            return Err(anyhow::anyhow!("迁移失败，目标文件`{new_path:?}`已存在"));
+        }
+        std::fs::rename(&old_path, &new_path)
+            .with_context(|| format!("将`{old_path:?}`重命名为`{new_path:?}`失败"))?;
+        Ok(format!("已将`{old_path:?}`迁移为`{new_path:?}`"))
+    }
+}
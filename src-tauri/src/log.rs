@@ -0,0 +1,83 @@
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use tauri::AppHandle;
+
+use crate::events::{LogEvent, LogEventPayload};
+use crate::extensions::IgnoreLockPoison;
+
+/// `FrontendLogState::recent`保留的最近日志条数上限
+const RECENT_LOG_CAPACITY: usize = 200;
+
+/// 日志级别，数值越大表示越严重，用于`FrontendLogState::min_level`按级别过滤
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub enum LogLevel {
+    Debug,
+    #[default]
+    Info,
+    Warn,
+    Error,
+}
+
+impl LogLevel {
+    fn from_u8(value: u8) -> Self {
+        match value {
+            0 => Self::Debug,
+            1 => Self::Info,
+            2 => Self::Warn,
+            _ => Self::Error,
+        }
+    }
+}
+
+/// 前端日志订阅状态：只有级别不低于`min_level`且未暂停时，`log_event`才会真正`emit`给前端，
+/// 避免日志量大(如批量下载报错刷屏)时频繁的IPC调用卡顿前端界面
+#[derive(Default)]
+pub struct FrontendLogState {
+    min_level: AtomicU8,
+    paused: AtomicBool,
+    /// 最近日志的环形缓冲区，不受`min_level`/`paused`影响，供[`crate::commands::collect_debug_bundle`]
+    /// 打包调试信息时使用，避免用户复现问题时前端日志面板恰好被过滤/暂停导致缺失关键日志
+    recent: Mutex<VecDeque<String>>,
+}
+
+impl FrontendLogState {
+    pub fn set_min_level(&self, level: LogLevel) {
+        self.min_level.store(level as u8, Ordering::Relaxed);
+    }
+
+    pub fn set_paused(&self, paused: bool) {
+        self.paused.store(paused, Ordering::Relaxed);
+    }
+
+    fn should_emit(&self, level: LogLevel) -> bool {
+        !self.paused.load(Ordering::Relaxed)
+            && level >= LogLevel::from_u8(self.min_level.load(Ordering::Relaxed))
+    }
+
+    fn push_recent(&self, level: LogLevel, message: &str) {
+        let mut recent = self.recent.lock_or_panic();
+        if recent.len() >= RECENT_LOG_CAPACITY {
+            recent.pop_front();
+        }
+        recent.push_back(format!("[{level:?}] {message}"));
+    }
+
+    /// 按时间顺序返回最近的日志，最多[`RECENT_LOG_CAPACITY`]条
+    pub fn recent(&self) -> Vec<String> {
+        self.recent.lock_or_panic().iter().cloned().collect()
+    }
+}
+
+/// 按`state`当前的级别/暂停设置过滤后，把一条日志推送给前端；无论是否被过滤，都会记录进`state`的最近日志缓冲区
+pub fn log_event(app: &AppHandle, state: &FrontendLogState, level: LogLevel, message: String) {
+    state.push_recent(level, &message);
+    if !state.should_emit(level) {
+        return;
+    }
+    crate::events::emit_event(app, LogEvent(LogEventPayload { level, message }));
+}
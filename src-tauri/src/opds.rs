@@ -0,0 +1,289 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Mutex, RwLock};
+
+use anyhow::Context;
+use axum::body::Body;
+use axum::extract::{Path, State};
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::middleware::Next;
+use axum::response::Response;
+use axum::routing::get;
+use axum::Router;
+use base64::Engine;
+use tauri::{AppHandle, Manager};
+use tokio::sync::oneshot;
+
+use crate::config::Config;
+use crate::extensions::IgnoreRwLockPoison;
+
+/// 持有正在运行的OPDS服务端的关闭信号，`None`表示当前没有在运行
+#[derive(Default)]
+pub struct OpdsHandle(pub Mutex<Option<oneshot::Sender<()>>>);
+
+/// 启动OPDS服务端，返回用于关闭它的信号发送端
+pub async fn start_server(app: AppHandle, port: u16) -> anyhow::Result<oneshot::Sender<()>> {
+    let listener = tokio::net::TcpListener::bind(("0.0.0.0", port))
+        .await
+        .with_context(|| format!("监听端口`{port}`失败，请检查端口是否已被占用"))?;
+
+    let router = Router::new()
+        .route("/opds", get(root_catalog))
+        .route("/opds/comics/:comic", get(comic_feed))
+        .route("/opds/cbz/:comic/:episode", get(download_cbz))
+        .layer(axum::middleware::from_fn_with_state(app.clone(), require_auth))
+        .with_state(app);
+
+    let (shutdown_tx, shutdown_rx) = oneshot::channel();
+    tokio::spawn(async move {
+        let _ = axum::serve(listener, router)
+            .with_graceful_shutdown(async {
+                let _ = shutdown_rx.await;
+            })
+            .await;
+    });
+
+    Ok(shutdown_tx)
+}
+
+/// 校验HTTP Basic Auth中的密码是否与`opds_password`一致，密码为空表示不需要鉴权
+async fn require_auth(
+    State(app): State<AppHandle>,
+    headers: HeaderMap,
+    request: axum::extract::Request,
+    next: Next,
+) -> Response {
+    let password = app
+        .state::<RwLock<Config>>()
+        .read_or_panic()
+        .opds_password
+        .clone();
+    if password.is_empty() {
+        return next.run(request).await;
+    }
+
+    let authorized = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Basic "))
+        .and_then(|encoded| base64::engine::general_purpose::STANDARD.decode(encoded).ok())
+        .and_then(|bytes| String::from_utf8(bytes).ok())
+        .is_some_and(|decoded| decoded.split_once(':').is_some_and(|(_, pass)| pass == password));
+
+    if authorized {
+        next.run(request).await
+    } else {
+        Response::builder()
+            .status(StatusCode::UNAUTHORIZED)
+            .header(header::WWW_AUTHENTICATE, r#"Basic realm="OPDS""#)
+            .body(Body::empty())
+            .unwrap_or_default()
+    }
+}
+
+/// 聚合所有库分区，建立`漫画标题 -> 所在库分区目录`的映射
+///
+/// 与`commands::create_comic_title_to_dir_map`逻辑一致，但这里直接持有`AppHandle`而非`tauri::State`
+fn comic_title_to_dir_map(app: &AppHandle) -> HashMap<String, PathBuf> {
+    let library_dirs = app.state::<RwLock<Config>>().read_or_panic().all_library_dirs();
+    let mut map = HashMap::new();
+    for library_dir in library_dirs {
+        let Ok(entries) = std::fs::read_dir(&library_dir.dir) else {
+            continue;
+        };
+        for entry in entries.filter_map(Result::ok) {
+            if entry.path().is_dir() {
+                let comic_title = entry.file_name().to_string_lossy().to_string();
+                map.entry(comic_title).or_insert_with(|| library_dir.dir.clone());
+            }
+        }
+    }
+    map
+}
+
+/// 校验URL路径中取来的单个路径段是否可以安全地拼接到库目录下：拒绝空段、`.`、`..`，
+/// 以及包含路径分隔符的段，防止[`download_cbz`]被用来构造`../`之类的段逃出章节目录
+fn is_safe_path_segment(segment: &str) -> bool {
+    !segment.is_empty()
+        && segment != "."
+        && segment != ".."
+        && !segment.contains('/')
+        && !segment.contains('\\')
+}
+
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn atom_response(body: String) -> Response {
+    Response::builder()
+        .header(
+            header::CONTENT_TYPE,
+            "application/atom+xml;charset=utf-8",
+        )
+        .body(Body::from(body))
+        .unwrap_or_default()
+}
+
+/// OPDS根目录，列出已下载的所有漫画
+async fn root_catalog(State(app): State<AppHandle>) -> Response {
+    let mut comic_titles: Vec<String> = comic_title_to_dir_map(&app).into_keys().collect();
+    comic_titles.sort();
+
+    let entries = comic_titles
+        .iter()
+        .map(|title| {
+            let escaped = xml_escape(title);
+            format!(
+                r#"<entry>
+<title>{escaped}</title>
+<id>urn:picacomic-downloader:comic:{escaped}</id>
+<updated>{now}</updated>
+<link rel="subsection" href="/opds/comics/{encoded}" type="application/atom+xml;profile=opds-catalog;kind=acquisition"/>
+</entry>"#,
+                now = chrono::Utc::now().to_rfc3339(),
+                encoded = urlencoding::encode(title),
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let feed = format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<feed xmlns="http://www.w3.org/2005/Atom" xmlns:dc="http://purl.org/dc/terms/">
+<id>urn:picacomic-downloader:root</id>
+<title>哔咔漫画本地库</title>
+<updated>{now}</updated>
+<link rel="self" href="/opds" type="application/atom+xml;profile=opds-catalog;kind=navigation"/>
+{entries}
+</feed>"#,
+        now = chrono::Utc::now().to_rfc3339(),
+    );
+
+    atom_response(feed)
+}
+
+/// 某本漫画下的章节列表，每个章节提供一个CBZ下载链接
+async fn comic_feed(State(app): State<AppHandle>, Path(comic): Path<String>) -> Response {
+    let Some(comic_dir) = comic_title_to_dir_map(&app).get(&comic).cloned() else {
+        return Response::builder()
+            .status(StatusCode::NOT_FOUND)
+            .body(Body::empty())
+            .unwrap_or_default();
+    };
+
+    let mut episode_names: Vec<String> = std::fs::read_dir(&comic_dir)
+        .into_iter()
+        .flatten()
+        .filter_map(Result::ok)
+        .filter(|entry| entry.path().is_dir())
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        .collect();
+    episode_names.sort();
+
+    let entries = episode_names
+        .iter()
+        .map(|episode| {
+            let escaped = xml_escape(episode);
+            format!(
+                r#"<entry>
+<title>{escaped}</title>
+<id>urn:picacomic-downloader:episode:{comic_escaped}:{escaped}</id>
+<updated>{now}</updated>
+<link rel="http://opds-spec.org/acquisition" href="/opds/cbz/{comic_encoded}/{episode_encoded}" type="application/vnd.comicbook+zip"/>
+</entry>"#,
+                comic_escaped = xml_escape(&comic),
+                now = chrono::Utc::now().to_rfc3339(),
+                comic_encoded = urlencoding::encode(&comic),
+                episode_encoded = urlencoding::encode(episode),
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let feed = format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<feed xmlns="http://www.w3.org/2005/Atom" xmlns:dc="http://purl.org/dc/terms/">
+<id>urn:picacomic-downloader:comic:{comic_escaped}</id>
+<title>{comic_escaped}</title>
+<updated>{now}</updated>
+<link rel="self" href="/opds/comics/{comic_encoded}" type="application/atom+xml;profile=opds-catalog;kind=acquisition"/>
+{entries}
+</feed>"#,
+        comic_escaped = xml_escape(&comic),
+        comic_encoded = urlencoding::encode(&comic),
+        now = chrono::Utc::now().to_rfc3339(),
+    );
+
+    atom_response(feed)
+}
+
+/// 即时把某个章节打包为CBZ并返回，供OPDS客户端下载阅读
+async fn download_cbz(
+    State(app): State<AppHandle>,
+    Path((comic, episode)): Path<(String, String)>,
+) -> Response {
+    if !is_safe_path_segment(&episode) {
+        return Response::builder()
+            .status(StatusCode::NOT_FOUND)
+            .body(Body::empty())
+            .unwrap_or_default();
+    }
+    let Some(comic_dir) = comic_title_to_dir_map(&app).get(&comic).cloned() else {
+        return Response::builder()
+            .status(StatusCode::NOT_FOUND)
+            .body(Body::empty())
+            .unwrap_or_default();
+    };
+    let ep_dir = comic_dir.join(&episode);
+    if !ep_dir.is_dir() {
+        return Response::builder()
+            .status(StatusCode::NOT_FOUND)
+            .body(Body::empty())
+            .unwrap_or_default();
+    }
+
+    let mut entries: Vec<PathBuf> = std::fs::read_dir(&ep_dir)
+        .into_iter()
+        .flatten()
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file())
+        .collect();
+    entries.sort();
+
+    let mut buffer = std::io::Cursor::new(Vec::new());
+    let mut zip = zip::ZipWriter::new(&mut buffer);
+    let options =
+        zip::write::SimpleFileOptions::default().compression_method(zip::CompressionMethod::Stored);
+    for path in entries {
+        let Some(file_name) = path.file_name().map(|n| n.to_string_lossy().to_string()) else {
+            continue;
+        };
+        if zip.start_file(file_name, options).is_err() {
+            continue;
+        }
+        let Ok(data) = std::fs::read(&path) else {
+            continue;
+        };
+        let _ = std::io::Write::write_all(&mut zip, &data);
+    }
+    if zip.finish().is_err() {
+        return Response::builder()
+            .status(StatusCode::INTERNAL_SERVER_ERROR)
+            .body(Body::empty())
+            .unwrap_or_default();
+    }
+
+    Response::builder()
+        .header(header::CONTENT_TYPE, "application/vnd.comicbook+zip")
+        .header(
+            header::CONTENT_DISPOSITION,
+            format!("attachment; filename=\"{episode}.cbz\""),
+        )
+        .body(Body::from(buffer.into_inner()))
+        .unwrap_or_default()
+}
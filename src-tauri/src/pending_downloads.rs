@@ -0,0 +1,53 @@
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+use crate::types::Episode;
+
+/// 尚未完成下载的章节队列，在应用运行期间随每次提交/结束实时持久化，
+/// 应用被强制退出或优雅停机时也不会丢失，下次启动时自动恢复下载
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PendingDownloadsStore {
+    episodes: Vec<Episode>,
+}
+
+impl PendingDownloadsStore {
+    pub fn new(app: &AppHandle) -> anyhow::Result<Self> {
+        let path = Self::path(app)?;
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let string = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&string).unwrap_or_default())
+    }
+
+    pub fn episodes(&self) -> Vec<Episode> {
+        self.episodes.clone()
+    }
+
+    pub fn add(&mut self, ep: Episode) {
+        if self.episodes.iter().any(|e| e.ep_id == ep.ep_id) {
+            return;
+        }
+        self.episodes.push(ep);
+    }
+
+    pub fn remove(&mut self, ep_id: &str) {
+        self.episodes.retain(|e| e.ep_id != ep_id);
+    }
+
+    pub fn save(&self, app: &AppHandle) -> anyhow::Result<()> {
+        let path = Self::path(app)?;
+        let string = serde_json::to_string_pretty(self)?;
+        std::fs::write(path, string)?;
+        Ok(())
+    }
+
+    fn path(app: &AppHandle) -> anyhow::Result<std::path::PathBuf> {
+        let app_data_dir = app
+            .path()
+            .app_data_dir()
+            .context("failed to get app data dir")?;
+        Ok(app_data_dir.join("pending_downloads.json"))
+    }
+}
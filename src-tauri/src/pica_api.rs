@@ -0,0 +1,69 @@
+use async_trait::async_trait;
+
+use crate::responses::{
+    AnnouncementRespData, CategoryRespData, ComicInFavoriteRespData, ComicInSearchRespData,
+    ComicRespData, CommentRespData, EpisodeImageRespData, EpisodeRespData, Pagination,
+    UserProfileDetailRespData,
+};
+use crate::types::{ApiChannelLatency, Sort};
+
+/// 抽象[`crate::pica_client::PicaClient`]对外暴露的所有哔咔API调用
+///
+/// `DownloadManager`与各个command都依赖该trait而非具体实现，从而可以在离线开发前端、
+/// 编写下载状态机端到端测试时，注入读取本地夹具的[`crate::pica_client_mock::MockPicaClient`]
+#[async_trait]
+pub trait PicaApi: Send + Sync {
+    async fn login(&self, email: &str, password: &str) -> anyhow::Result<String>;
+
+    async fn get_user_profile(&self) -> anyhow::Result<UserProfileDetailRespData>;
+
+    async fn search_comic(
+        &self,
+        keyword: &str,
+        sort: Sort,
+        page: i32,
+        categories: Vec<String>,
+    ) -> anyhow::Result<Pagination<ComicInSearchRespData>>;
+
+    async fn get_comic(&self, comic_id: &str) -> anyhow::Result<ComicRespData>;
+
+    async fn get_episode(
+        &self,
+        comic_id: &str,
+        page: i64,
+    ) -> anyhow::Result<Pagination<EpisodeRespData>>;
+
+    async fn get_episode_image(
+        &self,
+        comic_id: &str,
+        ep_order: i64,
+        page: i64,
+    ) -> anyhow::Result<Pagination<EpisodeImageRespData>>;
+
+    async fn get_favorite_comics(
+        &self,
+        sort: Sort,
+        page: i64,
+    ) -> anyhow::Result<Pagination<ComicInFavoriteRespData>>;
+
+    async fn get_announcements(
+        &self,
+        page: i64,
+    ) -> anyhow::Result<Pagination<AnnouncementRespData>>;
+
+    async fn get_categories(&self) -> anyhow::Result<Vec<CategoryRespData>>;
+
+    async fn get_comments(
+        &self,
+        comic_id: &str,
+        page: i64,
+    ) -> anyhow::Result<Pagination<CommentRespData>>;
+
+    async fn toggle_favorite(&self, comic_id: &str) -> anyhow::Result<bool>;
+
+    /// 点赞`comic_id`，返回操作后的点赞状态(`true`表示已点赞)
+    async fn like_comic(&self, comic_id: &str) -> anyhow::Result<bool>;
+
+    /// 依次用每条分流线路(`app-channel`)测速，见[`crate::pica_client::PicaClient::test_channels`]
+    async fn test_channels(&self) -> Vec<ApiChannelLatency>;
+}
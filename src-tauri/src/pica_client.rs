@@ -1,8 +1,10 @@
-use std::sync::RwLock;
-use std::time::Duration;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
 
 use anyhow::{anyhow, Context};
+use async_trait::async_trait;
 use chrono::Local;
+use dashmap::DashMap;
 use hmac::{Hmac, Mac};
 use reqwest_middleware::ClientWithMiddleware;
 use reqwest_retry::{Jitter, RetryTransientMiddleware};
@@ -10,68 +12,150 @@ use serde_json::json;
 use sha2::Sha256;
 use tauri::http::StatusCode;
 use tauri::{AppHandle, Manager};
+use tokio::sync::Mutex as AsyncMutex;
 
 use crate::config::Config;
 use crate::extensions::IgnoreRwLockPoison;
+use crate::pica_api::PicaApi;
 use crate::responses::{
-    ComicInFavoriteRespData, ComicInSearchRespData, ComicRespData, EpisodeImageRespData,
-    EpisodeRespData, GetComicRespData, GetEpisodeImageRespData, GetEpisodeRespData,
-    GetFavoriteRespData, LoginRespData, Pagination, PicaResp, SearchRespData,
-    UserProfileDetailRespData, UserProfileRespData,
+    AnnouncementRespData, CategoryRespData, ComicInFavoriteRespData, ComicInSearchRespData,
+    ComicRespData, CommentRespData, EpisodeImageRespData, EpisodeRespData, GetAnnouncementsRespData,
+    GetCategoriesRespData, GetComicRespData, GetCommentsRespData, GetEpisodeImageRespData,
+    GetEpisodeRespData, GetFavoriteRespData, LikeComicRespData, LoginRespData, Pagination,
+    PicaResp, SearchRespData, ToggleFavoriteRespData, UserProfileDetailRespData,
+    UserProfileRespData,
 };
-use crate::types::Sort;
+use crate::types::{ApiChannel, ApiChannelLatency, PicaChannel, Sort};
 
 const HOST_URL: &str = "https://picaapi.picacomic.com/";
 const API_KEY: &str = "C69BAF41DA5ABD1FFEDC6D2FEA56B";
 const NONCE: &str = "ptxdhmjzqtnrtwndhbxcpkjamb33w837";
 const DIGEST_KEY: &str = r#"~d}$Q7$eIni=V)9\RK/P.RM4;9[7|@/CA}b~OW!3?EV`:<>M7pddUBL5n|0/*Cn"#; //TODO: 去除没必要的#号
+/// `get_comic`结果的缓存有效期，收藏夹批量下载等场景可能短时间内重复查询同一漫画
+const GET_COMIC_CACHE_TTL: Duration = Duration::from_secs(30);
+
+type ComicCacheEntry = Arc<AsyncMutex<Option<(Instant, ComicRespData)>>>;
+
+/// 解析失败时落盘的dump文件名，存放于`app_data_dir`下
+pub const PARSE_FAILURE_DUMP_FILENAME: &str = "last_parse_failure.json";
+
+/// 把解析失败的原始`data`落盘到`app_data_dir/last_parse_failure.json`，覆盖上一次的记录，
+/// 供用户通过[`crate::commands::collect_debug_bundle`]一键收集后提交issue；落盘失败只忽略，不影响主流程报错
+fn dump_parse_failure(app: &AppHandle, data_str: &str) {
+    let Ok(app_data_dir) = app.path().app_data_dir() else {
+        return;
+    };
+    let _ = std::fs::write(app_data_dir.join(PARSE_FAILURE_DUMP_FILENAME), data_str);
+}
 
 #[derive(Clone)]
 pub struct PicaClient {
     app: AppHandle,
+    /// `get_comic`的结果缓存，key为`comic_id`
+    ///
+    /// value用`tokio::sync::Mutex`包裹，同一`comic_id`的并发请求会自动排队，
+    /// 后来者在获得锁后会发现缓存已被前面的请求填充，从而避免重复的网络请求(in-flight去重)
+    comic_cache: Arc<DashMap<String, ComicCacheEntry>>,
 }
 
 impl PicaClient {
     pub fn new(app: AppHandle) -> Self {
-        Self { app }
+        Self {
+            app,
+            comic_cache: Arc::new(DashMap::new()),
+        }
     }
 
     // TODO: 用api_client和img_client分别处理api请求和图片请求，避免每次请求都创建client
-    pub fn client() -> ClientWithMiddleware {
+    pub fn client(&self) -> ClientWithMiddleware {
         // TODO: 可以将retry_policy缓存起来，避免每次请求都创建
         let retry_policy = reqwest_retry::policies::ExponentialBackoff::builder()
             .base(1) // 指数为1，保证重试间隔为1秒不变
             .jitter(Jitter::Bounded) // 重试间隔在1秒左右波动
             .build_with_total_retry_duration(Duration::from_secs(3)); // 重试总时长为3秒
-        let client = reqwest::ClientBuilder::new()
-            .timeout(Duration::from_secs(2)) // 每个请求超过2秒就超时
-            .build()
-            .unwrap();
+        let config = self.app.state::<RwLock<Config>>().read_or_panic();
+        let dns_overrides = config.dns_overrides.clone();
+        let mut builder = reqwest::ClientBuilder::new().timeout(Duration::from_secs(2)); // 每个请求超过2秒就超时
+        // 把域名直接解析到固定IP，绕过被污染的DNS，同时仍用原域名握手TLS(保留SNI)
+        for (host, ip) in dns_overrides {
+            let Ok(ip) = ip.parse() else { continue };
+            builder = builder.resolve(&host, std::net::SocketAddr::new(ip, 443));
+        }
+        builder = config.apply_proxy(builder);
+        drop(config);
+        let client = builder.build().unwrap();
         reqwest_middleware::ClientBuilder::new(client)
             .with(RetryTransientMiddleware::new_with_policy(retry_policy))
             .build()
     }
 
+    /// 根据`Config.pica_channel`/`pica_backup_host`决定本次请求优先使用的域名，以及
+    /// 该域名请求失败时可自动重试的备用域名(`None`表示没有可用的备用域名)
+    fn resolve_hosts(&self) -> (String, Option<String>) {
+        let config = self.app.state::<RwLock<Config>>().read_or_panic();
+        let backup_host = config.pica_backup_host.clone();
+        match config.pica_channel {
+            PicaChannel::Auto => (HOST_URL.to_string(), backup_host),
+            PicaChannel::Primary => (HOST_URL.to_string(), None),
+            PicaChannel::Backup => match backup_host {
+                Some(backup_host) => (backup_host, None),
+                None => (HOST_URL.to_string(), None),
+            },
+        }
+    }
+
+    /// 当前配置的`app-channel`分流线路，见[`crate::types::ApiChannel`]
+    fn current_channel(&self) -> ApiChannel {
+        self.app.state::<RwLock<Config>>().read_or_panic().api_channel
+    }
+
+    /// 请求优先域名失败时，如果配置了备用域名会自动切换重试一次，提高API被风控时的可用性；
+    /// 备用域名复用与主域名相同的签名算法(官方是否存在独立签名方案的Web版接口尚未确认)
     async fn pica_request(
         &self,
         method: reqwest::Method,
         path: &str,
         payload: Option<serde_json::Value>,
+    ) -> anyhow::Result<reqwest::Response> {
+        let (host, backup_host) = self.resolve_hosts();
+        let channel = self.current_channel();
+        match self
+            .pica_request_via(&host, method.clone(), path, payload.clone(), channel)
+            .await
+        {
+            Ok(http_resp) => Ok(http_resp),
+            Err(err) => {
+                let Some(backup_host) = backup_host else {
+                    return Err(err);
+                };
+                eprintln!("warn: 请求`{host}`失败，尝试切换到备用域名`{backup_host}`重试: {err}");
+                self.pica_request_via(&backup_host, method, path, payload, channel)
+                    .await
+            }
+        }
+    }
+
+    async fn pica_request_via(
+        &self,
+        host: &str,
+        method: reqwest::Method,
+        path: &str,
+        payload: Option<serde_json::Value>,
+        channel: ApiChannel,
     ) -> anyhow::Result<reqwest::Response> {
         let time = Local::now().timestamp().to_string();
         let signature = create_signature(path, &method, &time)?;
-        let token = self
-            .app
-            .state::<RwLock<Config>>()
-            .read_or_panic()
-            .token
-            .clone();
+        let config = self.app.state::<RwLock<Config>>().read_or_panic();
+        let token = config.token.clone();
+        let image_quality = config.image_quality;
+        drop(config);
 
-        let request = Self::client()
-            .request(method.clone(), format!("{HOST_URL}{path}").as_str())
+        let request = self
+            .client()
+            .request(method.clone(), format!("{host}{path}").as_str())
             .header("api-key", API_KEY)
             .header("accept", "application/vnd.picacomic.com.v1+json")
-            .header("app-channel", "2")
+            .header("app-channel", channel.header_value())
             .header("time", time)
             .header("nonce", NONCE)
             .header("app-version", "2.2.1.2.3.3")
@@ -81,7 +165,7 @@ impl PicaClient {
             .header("Content-Type", "application/json; charset=UTF-8")
             .header("User-Agent", "okhttp/3.8.1")
             .header("authorization", token)
-            .header("image-quality", "original")
+            .header("image-quality", image_quality.header_value())
             .header("signature", signature);
 
         let http_resp = match payload {
@@ -112,6 +196,29 @@ impl PicaClient {
             .await
     }
 
+    /// 依次用每条分流线路(`app-channel`)请求`categories`接口测速，返回各线路的延迟，
+    /// 请求失败的线路延迟为`None`并附带错误信息；不改变`Config.api_channel`，由用户根据结果自行切换
+    pub async fn test_channels(&self) -> Vec<ApiChannelLatency> {
+        let (host, _) = self.resolve_hosts();
+        let mut results = Vec::new();
+        for channel in ApiChannel::all() {
+            let start = Instant::now();
+            let result = self
+                .pica_request_via(&host, reqwest::Method::GET, "categories", None, channel)
+                .await;
+            let (latency_ms, error) = match result {
+                Ok(_) => (Some(start.elapsed().as_millis() as u64), None),
+                Err(err) => (None, Some(err.to_string())),
+            };
+            results.push(ApiChannelLatency {
+                channel,
+                latency_ms,
+                error,
+            });
+        }
+        results
+    }
+
     pub async fn login(&self, email: &str, password: &str) -> anyhow::Result<String> {
         let payload = json!({
             "email": email,
@@ -230,7 +337,25 @@ impl PicaClient {
         Ok(search_resp_data.comics)
     }
 
+    /// 获取漫画详情，短时间内重复查询同一`comic_id`会命中缓存，并发查询同一`comic_id`会自动合并为一次网络请求
     pub async fn get_comic(&self, comic_id: &str) -> anyhow::Result<ComicRespData> {
+        let entry = self
+            .comic_cache
+            .entry(comic_id.to_string())
+            .or_insert_with(|| Arc::new(AsyncMutex::new(None)))
+            .clone();
+        let mut slot = entry.lock().await;
+        if let Some((fetched_at, comic)) = slot.as_ref() {
+            if fetched_at.elapsed() < GET_COMIC_CACHE_TTL {
+                return Ok(comic.clone());
+            }
+        }
+        let comic = self.fetch_comic(comic_id).await?;
+        *slot = Some((Instant::now(), comic.clone()));
+        Ok(comic)
+    }
+
+    async fn fetch_comic(&self, comic_id: &str) -> anyhow::Result<ComicRespData> {
         // 发送获取漫画请求
         let path = format!("comics/{comic_id}");
         let http_resp = self.pica_get(&path).await?;
@@ -310,11 +435,11 @@ impl PicaClient {
         };
         // 尝试将data解析为GetEpisodeRespData
         let data_str = data.to_string();
-        let get_episode_resp_data = serde_json::from_str::<GetEpisodeRespData>(&data_str).context(
-            format!(
+        let get_episode_resp_data = serde_json::from_str::<GetEpisodeRespData>(&data_str)
+            .inspect_err(|_| dump_parse_failure(&self.app, &data_str))
+            .context(format!(
                 "获取漫画`{comic_id}`的章节分页`{page}`失败，将data解析为GetEpisodeRespData失败: {data_str}"
-            ),
-        )?;
+            ))?;
 
         Ok(get_episode_resp_data.eps)
     }
@@ -408,6 +533,291 @@ impl PicaClient {
 
         Ok(get_favorite_resp_data.comics)
     }
+
+    /// 获取官方公告/声明，官方App启动时会拉取该接口展示接口维护等信息
+    pub async fn get_announcements(
+        &self,
+        page: i64,
+    ) -> anyhow::Result<Pagination<AnnouncementRespData>> {
+        // 发送获取公告请求
+        let path = format!("announcements?page={page}");
+        let http_resp = self.pica_get(&path).await?;
+        // 检查http响应状态码
+        let status = http_resp.status();
+        let body = http_resp.text().await?;
+        if status == StatusCode::UNAUTHORIZED {
+            return Err(anyhow!(
+                "获取公告失败，Authorization无效或已过期，请重新登录({status}): {body}"
+            ));
+        } else if status != StatusCode::OK {
+            return Err(anyhow!("获取公告失败，预料之外的状态码({status}): {body}"));
+        }
+        // 尝试将body解析为PicaResp
+        let pica_resp: PicaResp = serde_json::from_str(&body)
+            .context(format!("获取公告失败，将body解析为PicaResp失败: {body}"))?;
+        // 检查PicaResp的code字段
+        if pica_resp.code != 200 {
+            return Err(anyhow!("获取公告失败，预料之外的code: {pica_resp:?}"));
+        }
+        // 检查PicaResp的data是否存在
+        let Some(data) = pica_resp.data else {
+            return Err(anyhow!("获取公告失败，data字段不存在: {pica_resp:?}"));
+        };
+        // 尝试将data解析为GetAnnouncementsRespData
+        let data_str = data.to_string();
+        let get_announcements_resp_data = serde_json::from_str::<GetAnnouncementsRespData>(
+            &data_str,
+        )
+        .context(format!(
+            "获取公告失败，将data解析为GetAnnouncementsRespData失败: {data_str}"
+        ))?;
+
+        Ok(get_announcements_resp_data.announcements)
+    }
+
+    /// 获取官方分类列表，用于搜索/分类筛选，取代前端硬编码的分类
+    pub async fn get_categories(&self) -> anyhow::Result<Vec<CategoryRespData>> {
+        // 发送获取分类请求
+        let http_resp = self.pica_get("categories").await?;
+        // 检查http响应状态码
+        let status = http_resp.status();
+        let body = http_resp.text().await?;
+        if status == StatusCode::UNAUTHORIZED {
+            return Err(anyhow!(
+                "获取分类失败，Authorization无效或已过期，请重新登录({status}): {body}"
+            ));
+        } else if status != StatusCode::OK {
+            return Err(anyhow!("获取分类失败，预料之外的状态码({status}): {body}"));
+        }
+        // 尝试将body解析为PicaResp
+        let pica_resp: PicaResp = serde_json::from_str(&body)
+            .context(format!("获取分类失败，将body解析为PicaResp失败: {body}"))?;
+        // 检查PicaResp的code字段
+        if pica_resp.code != 200 {
+            return Err(anyhow!("获取分类失败，预料之外的code: {pica_resp:?}"));
+        }
+        // 检查PicaResp的data是否存在
+        let Some(data) = pica_resp.data else {
+            return Err(anyhow!("获取分类失败，data字段不存在: {pica_resp:?}"));
+        };
+        // 尝试将data解析为GetCategoriesRespData
+        let data_str = data.to_string();
+        let get_categories_resp_data = serde_json::from_str::<GetCategoriesRespData>(&data_str)
+            .context(format!(
+                "获取分类失败，将data解析为GetCategoriesRespData失败: {data_str}"
+            ))?;
+
+        Ok(get_categories_resp_data.categories)
+    }
+
+    /// 获取漫画`comic_id`的评论分页，下载前可用于在工具内预览评论判断质量
+    pub async fn get_comments(
+        &self,
+        comic_id: &str,
+        page: i64,
+    ) -> anyhow::Result<Pagination<CommentRespData>> {
+        // 发送获取漫画评论分页请求
+        let path = format!("comics/{comic_id}/comments?page={page}");
+        let http_resp = self.pica_get(&path).await?;
+        // 检查http响应状态码
+        let status = http_resp.status();
+        let body = http_resp.text().await?;
+        if status == StatusCode::UNAUTHORIZED {
+            return Err(anyhow!(
+                "获取漫画`{comic_id}`的评论分页`{page}`失败，Authorization无效或已过期，请重新登录({status}): {body}"
+            ));
+        } else if status != StatusCode::OK {
+            return Err(anyhow!(
+                "获取漫画`{comic_id}`的评论分页`{page}`失败，预料之外的状态码({status}): {body}"
+            ));
+        }
+        // 尝试将body解析为PicaResp
+        let pica_resp: PicaResp = serde_json::from_str(&body).context(format!(
+            "获取漫画`{comic_id}`的评论分页`{page}`失败，将body解析为PicaResp失败: {body}"
+        ))?;
+        // 检查PicaResp的code字段
+        if pica_resp.code != 200 {
+            return Err(anyhow!(
+                "获取漫画`{comic_id}`的评论分页`{page}`失败，预料之外的code: {pica_resp:?}"
+            ));
+        }
+        // 检查PicaResp的data是否存在
+        let Some(data) = pica_resp.data else {
+            return Err(anyhow!(
+                "获取漫画`{comic_id}`的评论分页`{page}`失败，data字段不存在: {pica_resp:?}"
+            ));
+        };
+        // 尝试将data解析为GetCommentsRespData
+        let data_str = data.to_string();
+        let get_comments_resp_data = serde_json::from_str::<GetCommentsRespData>(&data_str)
+            .context(format!(
+                "获取漫画`{comic_id}`的评论分页`{page}`失败，将data解析为GetCommentsRespData失败: {data_str}"
+            ))?;
+
+        Ok(get_comments_resp_data.comments)
+    }
+
+    /// 切换`comic_id`的收藏状态(收藏<->取消收藏)，返回操作后的状态(`true`表示已收藏)
+    pub async fn toggle_favorite(&self, comic_id: &str) -> anyhow::Result<bool> {
+        // 发送切换收藏状态请求，该接口不需要请求体
+        let path = format!("comics/{comic_id}/favourite");
+        let http_resp = self
+            .pica_request(reqwest::Method::POST, &path, None)
+            .await?;
+        // 检查http响应状态码
+        let status = http_resp.status();
+        let body = http_resp.text().await?;
+        if status == StatusCode::UNAUTHORIZED {
+            return Err(anyhow!(
+                "切换收藏状态失败，Authorization无效或已过期，请重新登录({status}): {body}"
+            ));
+        } else if status != StatusCode::OK {
+            return Err(anyhow!("切换收藏状态失败，预料之外的状态码({status}): {body}"));
+        }
+        // 尝试将body解析为PicaResp
+        let pica_resp: PicaResp = serde_json::from_str(&body)
+            .context(format!("切换收藏状态失败，将body解析为PicaResp失败: {body}"))?;
+        // 检查PicaResp的code字段
+        if pica_resp.code != 200 {
+            return Err(anyhow!("切换收藏状态失败，预料之外的code: {pica_resp:?}"));
+        }
+        // 检查PicaResp的data是否存在
+        let Some(data) = pica_resp.data else {
+            return Err(anyhow!("切换收藏状态失败，data字段不存在: {pica_resp:?}"));
+        };
+        // 尝试将data解析为ToggleFavoriteRespData
+        let data_str = data.to_string();
+        let toggle_favorite_resp_data = serde_json::from_str::<ToggleFavoriteRespData>(&data_str)
+            .context(format!(
+                "切换收藏状态失败，将data解析为ToggleFavoriteRespData失败: {data_str}"
+            ))?;
+
+        Ok(toggle_favorite_resp_data.action == "favourite")
+    }
+
+    /// 点赞`comic_id`，返回操作后的点赞状态(`true`表示已点赞)
+    pub async fn like_comic(&self, comic_id: &str) -> anyhow::Result<bool> {
+        // 发送点赞请求，该接口不需要请求体
+        let path = format!("comics/{comic_id}/like");
+        let http_resp = self
+            .pica_request(reqwest::Method::POST, &path, None)
+            .await?;
+        // 检查http响应状态码
+        let status = http_resp.status();
+        let body = http_resp.text().await?;
+        if status == StatusCode::UNAUTHORIZED {
+            return Err(anyhow!(
+                "点赞漫画`{comic_id}`失败，Authorization无效或已过期，请重新登录({status}): {body}"
+            ));
+        } else if status != StatusCode::OK {
+            return Err(anyhow!(
+                "点赞漫画`{comic_id}`失败，预料之外的状态码({status}): {body}"
+            ));
+        }
+        // 尝试将body解析为PicaResp
+        let pica_resp: PicaResp = serde_json::from_str(&body).context(format!(
+            "点赞漫画`{comic_id}`失败，将body解析为PicaResp失败: {body}"
+        ))?;
+        // 检查PicaResp的code字段
+        if pica_resp.code != 200 {
+            return Err(anyhow!(
+                "点赞漫画`{comic_id}`失败，预料之外的code: {pica_resp:?}"
+            ));
+        }
+        // 检查PicaResp的data是否存在
+        let Some(data) = pica_resp.data else {
+            return Err(anyhow!("点赞漫画`{comic_id}`失败，data字段不存在: {pica_resp:?}"));
+        };
+        // 尝试将data解析为LikeComicRespData
+        let data_str = data.to_string();
+        let like_comic_resp_data = serde_json::from_str::<LikeComicRespData>(&data_str)
+            .context(format!(
+                "点赞漫画`{comic_id}`失败，将data解析为LikeComicRespData失败: {data_str}"
+            ))?;
+
+        Ok(like_comic_resp_data.action == "like")
+    }
+}
+
+#[async_trait]
+impl PicaApi for PicaClient {
+    async fn login(&self, email: &str, password: &str) -> anyhow::Result<String> {
+        self.login(email, password).await
+    }
+
+    async fn get_user_profile(&self) -> anyhow::Result<UserProfileDetailRespData> {
+        self.get_user_profile().await
+    }
+
+    async fn search_comic(
+        &self,
+        keyword: &str,
+        sort: Sort,
+        page: i32,
+        categories: Vec<String>,
+    ) -> anyhow::Result<Pagination<ComicInSearchRespData>> {
+        self.search_comic(keyword, sort, page, categories).await
+    }
+
+    async fn get_comic(&self, comic_id: &str) -> anyhow::Result<ComicRespData> {
+        self.get_comic(comic_id).await
+    }
+
+    async fn get_episode(
+        &self,
+        comic_id: &str,
+        page: i64,
+    ) -> anyhow::Result<Pagination<EpisodeRespData>> {
+        self.get_episode(comic_id, page).await
+    }
+
+    async fn get_episode_image(
+        &self,
+        comic_id: &str,
+        ep_order: i64,
+        page: i64,
+    ) -> anyhow::Result<Pagination<EpisodeImageRespData>> {
+        self.get_episode_image(comic_id, ep_order, page).await
+    }
+
+    async fn get_favorite_comics(
+        &self,
+        sort: Sort,
+        page: i64,
+    ) -> anyhow::Result<Pagination<ComicInFavoriteRespData>> {
+        self.get_favorite_comics(sort, page).await
+    }
+
+    async fn get_announcements(
+        &self,
+        page: i64,
+    ) -> anyhow::Result<Pagination<AnnouncementRespData>> {
+        self.get_announcements(page).await
+    }
+
+    async fn get_categories(&self) -> anyhow::Result<Vec<CategoryRespData>> {
+        self.get_categories().await
+    }
+
+    async fn get_comments(
+        &self,
+        comic_id: &str,
+        page: i64,
+    ) -> anyhow::Result<Pagination<CommentRespData>> {
+        self.get_comments(comic_id, page).await
+    }
+
+    async fn toggle_favorite(&self, comic_id: &str) -> anyhow::Result<bool> {
+        self.toggle_favorite(comic_id).await
+    }
+
+    async fn like_comic(&self, comic_id: &str) -> anyhow::Result<bool> {
+        self.like_comic(comic_id).await
+    }
+
+    async fn test_channels(&self) -> Vec<ApiChannelLatency> {
+        self.test_channels().await
+    }
 }
 
 fn create_signature(path: &str, method: &reqwest::Method, time: &str) -> anyhow::Result<String> {
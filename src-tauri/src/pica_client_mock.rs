@@ -0,0 +1,144 @@
+use anyhow::Context;
+use async_trait::async_trait;
+
+use crate::pica_api::PicaApi;
+use crate::responses::{
+    AnnouncementRespData, CategoryRespData, ComicInFavoriteRespData, ComicInSearchRespData,
+    ComicRespData, CommentRespData, EpisodeImageRespData, EpisodeRespData, Pagination,
+    UserProfileDetailRespData,
+};
+use crate::types::{ApiChannel, ApiChannelLatency, Sort};
+
+/// 从本地JSON夹具读取数据的[`PicaApi`]实现，不发起任何网络请求
+///
+/// 用于在没有哔咔账号/网络的情况下离线开发前端，以及编写下载状态机的端到端测试，
+/// 通过`mock-pica`这个Cargo feature启用，夹具位于`src-tauri/fixtures/mock_pica`
+pub struct MockPicaClient;
+
+impl MockPicaClient {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn parse_fixture<T: serde::de::DeserializeOwned>(name: &str, json: &str) -> anyhow::Result<T> {
+        serde_json::from_str(json).context(format!("解析mock夹具`{name}`失败"))
+    }
+}
+
+impl Default for MockPicaClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl PicaApi for MockPicaClient {
+    async fn login(&self, _email: &str, _password: &str) -> anyhow::Result<String> {
+        Ok("mock-token".to_string())
+    }
+
+    async fn get_user_profile(&self) -> anyhow::Result<UserProfileDetailRespData> {
+        Self::parse_fixture(
+            "user_profile.json",
+            include_str!("../fixtures/mock_pica/user_profile.json"),
+        )
+    }
+
+    async fn search_comic(
+        &self,
+        _keyword: &str,
+        _sort: Sort,
+        _page: i32,
+        _categories: Vec<String>,
+    ) -> anyhow::Result<Pagination<ComicInSearchRespData>> {
+        Self::parse_fixture(
+            "search_comic.json",
+            include_str!("../fixtures/mock_pica/search_comic.json"),
+        )
+    }
+
+    async fn get_comic(&self, _comic_id: &str) -> anyhow::Result<ComicRespData> {
+        Self::parse_fixture("comic.json", include_str!("../fixtures/mock_pica/comic.json"))
+    }
+
+    async fn get_episode(
+        &self,
+        _comic_id: &str,
+        _page: i64,
+    ) -> anyhow::Result<Pagination<EpisodeRespData>> {
+        Self::parse_fixture(
+            "episodes.json",
+            include_str!("../fixtures/mock_pica/episodes.json"),
+        )
+    }
+
+    async fn get_episode_image(
+        &self,
+        _comic_id: &str,
+        _ep_order: i64,
+        _page: i64,
+    ) -> anyhow::Result<Pagination<EpisodeImageRespData>> {
+        Self::parse_fixture(
+            "episode_images.json",
+            include_str!("../fixtures/mock_pica/episode_images.json"),
+        )
+    }
+
+    async fn get_favorite_comics(
+        &self,
+        _sort: Sort,
+        _page: i64,
+    ) -> anyhow::Result<Pagination<ComicInFavoriteRespData>> {
+        Self::parse_fixture(
+            "favorite_comics.json",
+            include_str!("../fixtures/mock_pica/favorite_comics.json"),
+        )
+    }
+
+    async fn get_announcements(
+        &self,
+        _page: i64,
+    ) -> anyhow::Result<Pagination<AnnouncementRespData>> {
+        Self::parse_fixture(
+            "announcements.json",
+            include_str!("../fixtures/mock_pica/announcements.json"),
+        )
+    }
+
+    async fn get_categories(&self) -> anyhow::Result<Vec<CategoryRespData>> {
+        Self::parse_fixture(
+            "categories.json",
+            include_str!("../fixtures/mock_pica/categories.json"),
+        )
+    }
+
+    async fn get_comments(
+        &self,
+        _comic_id: &str,
+        _page: i64,
+    ) -> anyhow::Result<Pagination<CommentRespData>> {
+        Self::parse_fixture(
+            "comments.json",
+            include_str!("../fixtures/mock_pica/comments.json"),
+        )
+    }
+
+    async fn toggle_favorite(&self, _comic_id: &str) -> anyhow::Result<bool> {
+        Ok(true)
+    }
+
+    async fn like_comic(&self, _comic_id: &str) -> anyhow::Result<bool> {
+        Ok(true)
+    }
+
+    async fn test_channels(&self) -> Vec<ApiChannelLatency> {
+        ApiChannel::all()
+            .into_iter()
+            .map(|channel| ApiChannelLatency {
+                channel,
+                latency_ms: Some(0),
+                error: None,
+            })
+            .collect()
+    }
+}
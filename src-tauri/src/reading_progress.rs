@@ -0,0 +1,56 @@
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use tauri::{AppHandle, Manager};
+
+/// 单个章节的本地阅读进度
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct EpisodeProgress {
+    pub ep_id: String,
+    pub current_page: i64,
+    pub is_finished: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReadingProgressStore {
+    episodes: Vec<EpisodeProgress>,
+}
+
+impl ReadingProgressStore {
+    pub fn new(app: &AppHandle) -> anyhow::Result<Self> {
+        let path = Self::path(app)?;
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&content).unwrap_or_default())
+    }
+
+    pub fn get(&self, ep_id: &str) -> Option<EpisodeProgress> {
+        self.episodes.iter().find(|p| p.ep_id == ep_id).cloned()
+    }
+
+    pub fn set(&mut self, progress: EpisodeProgress) {
+        if let Some(existing) = self.episodes.iter_mut().find(|p| p.ep_id == progress.ep_id) {
+            *existing = progress;
+        } else {
+            self.episodes.push(progress);
+        }
+    }
+
+    pub fn save(&self, app: &AppHandle) -> anyhow::Result<()> {
+        let path = Self::path(app)?;
+        let content = serde_json::to_string_pretty(self)?;
+        std::fs::write(path, content)?;
+        Ok(())
+    }
+
+    fn path(app: &AppHandle) -> anyhow::Result<std::path::PathBuf> {
+        let app_data_dir = app
+            .path()
+            .app_data_dir()
+            .context("failed to get app data dir")?;
+        Ok(app_data_dir.join("reading_progress.json"))
+    }
+}
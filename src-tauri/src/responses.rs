@@ -128,6 +128,10 @@ pub struct EpisodeRespData {
     pub order: i64,
     #[serde(rename = "updated_at")]
     pub updated_at: DateTime<Utc>,
+    /// 该章节是否为锁定/付费章节，目前的哔咔接口不会返回此字段，加`#[serde(default)]`
+    /// 是为了在接口开始返回该字段时能直接识别，暂时不影响现有解析
+    #[serde(default)]
+    pub is_locked: bool,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -166,6 +170,78 @@ pub struct ComicInFavoriteRespData {
     pub likes_count: i32,
 }
 
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetAnnouncementsRespData {
+    pub announcements: Pagination<AnnouncementRespData>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetCategoriesRespData {
+    pub categories: Vec<CategoryRespData>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetCommentsRespData {
+    pub comments: Pagination<CommentRespData>,
+}
+
+/// 漫画评论，见[`crate::pica_client::PicaClient::get_comments`]
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct CommentRespData {
+    #[serde(rename = "_id")]
+    pub id: String,
+    pub content: String,
+    #[serde(rename = "_user")]
+    pub user: CreatorRespData,
+    pub likes_count: i64,
+    pub comments_count: i64,
+    pub is_liked: bool,
+    pub is_top: bool,
+    pub hide: bool,
+    #[serde(rename = "created_at")]
+    pub created_at: DateTime<Utc>,
+}
+
+/// 官方分类，搜索/分类筛选时可选的分类列表，随官方更新而变化，不应由前端硬编码
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct CategoryRespData {
+    pub title: String,
+    pub thumb: ImageRespData,
+    /// 该分类是否为网页版专属，客户端通常应隐藏这类分类
+    pub is_web: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToggleFavoriteRespData {
+    /// 操作后的收藏状态，取值为`favourite`或`un_favourite`
+    pub action: String,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LikeComicRespData {
+    /// 操作后的点赞状态，取值为`like`或`un_like`
+    pub action: String,
+}
+
+/// 官方App启动时拉取的公告/声明，例如接口维护通知
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct AnnouncementRespData {
+    #[serde(rename = "_id")]
+    pub id: String,
+    pub title: String,
+    pub content: String,
+    #[serde(rename = "createdAt")]
+    pub created_at: DateTime<Utc>,
+}
+
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
 #[serde(rename_all = "camelCase")]
 pub struct Pagination<T> {
@@ -0,0 +1,146 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use anyhow::Context;
+use axum::body::Body;
+use axum::extract::{Path, State};
+use axum::http::{header, StatusCode};
+use axum::response::Response;
+use axum::routing::get;
+use axum::Router;
+use chrono::{DateTime, Utc};
+use path_slash::PathExt;
+use tauri::{AppHandle, Manager};
+use tokio::sync::oneshot;
+
+use crate::extensions::IgnoreLockPoison;
+
+/// 一次局域网分享会话：token对应着某本漫画的本地目录与过期时间
+pub struct ShareSession {
+    pub comic_title: String,
+    pub dir: PathBuf,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// 分享服务端的运行状态：正在运行的服务端关闭信号，以及所有有效的分享会话
+#[derive(Default)]
+pub struct ShareState {
+    pub server: Mutex<Option<oneshot::Sender<()>>>,
+    pub sessions: Mutex<HashMap<String, ShareSession>>,
+}
+
+/// 生成一个随机的分享token，用CSPRNG产生16字节随机数再转为hex，足够长以防止被局域网内其它设备猜中
+pub fn generate_token() -> String {
+    use rand::RngCore;
+    let mut bytes = [0u8; 16];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+/// 通过连接一个公网地址来获取本机在局域网中的IP，不会真正发送数据（UDP未连接不产生流量）
+pub fn local_ip() -> anyhow::Result<std::net::IpAddr> {
+    let socket = std::net::UdpSocket::bind("0.0.0.0:0").context("绑定本地UDP端口失败")?;
+    socket
+        .connect("223.5.5.5:80")
+        .context("探测局域网IP失败")?;
+    Ok(socket.local_addr()?.ip())
+}
+
+/// 如果分享服务端尚未启动，则启动它；已经在运行时什么都不做
+pub async fn ensure_server_running(app: AppHandle, port: u16) -> anyhow::Result<()> {
+    let share_state = app.state::<ShareState>();
+    if share_state.server.lock_or_panic().is_some() {
+        return Ok(());
+    }
+
+    let listener = tokio::net::TcpListener::bind(("0.0.0.0", port))
+        .await
+        .with_context(|| format!("监听端口`{port}`失败，请检查端口是否已被占用"))?;
+
+    let router = Router::new()
+        .route("/share/:token", get(download_share))
+        .with_state(app.clone());
+
+    let (shutdown_tx, shutdown_rx) = oneshot::channel();
+    tokio::spawn(async move {
+        let _ = axum::serve(listener, router)
+            .with_graceful_shutdown(async {
+                let _ = shutdown_rx.await;
+            })
+            .await;
+    });
+
+    *share_state.server.lock_or_panic() = Some(shutdown_tx);
+    Ok(())
+}
+
+/// 关闭分享服务端，使所有分享链接立即失效
+pub fn stop_server(app: &AppHandle) {
+    let share_state = app.state::<ShareState>();
+    if let Some(shutdown_tx) = share_state.server.lock_or_panic().take() {
+        let _ = shutdown_tx.send(());
+    }
+    share_state.sessions.lock_or_panic().clear();
+}
+
+/// 按token把对应漫画目录即时打包为ZIP并返回，过期或不存在的token一律404
+async fn download_share(State(app): State<AppHandle>, Path(token): Path<String>) -> Response {
+    let share_state = app.state::<ShareState>();
+    let session_dir_title = {
+        let sessions = share_state.sessions.lock_or_panic();
+        sessions.get(&token).and_then(|session| {
+            if session.expires_at < Utc::now() {
+                None
+            } else {
+                Some((session.dir.clone(), session.comic_title.clone()))
+            }
+        })
+    };
+    let Some((dir, title)) = session_dir_title else {
+        return Response::builder()
+            .status(StatusCode::NOT_FOUND)
+            .body(Body::empty())
+            .unwrap_or_default();
+    };
+
+    let mut buffer = std::io::Cursor::new(Vec::new());
+    let mut zip = zip::ZipWriter::new(&mut buffer);
+    let options =
+        zip::write::SimpleFileOptions::default().compression_method(zip::CompressionMethod::Stored);
+    if add_dir_to_zip(&mut zip, &dir, &dir, options).is_err() || zip.finish().is_err() {
+        return Response::builder()
+            .status(StatusCode::INTERNAL_SERVER_ERROR)
+            .body(Body::empty())
+            .unwrap_or_default();
+    }
+
+    Response::builder()
+        .header(header::CONTENT_TYPE, "application/zip")
+        .header(
+            header::CONTENT_DISPOSITION,
+            format!("attachment; filename=\"{title}.zip\""),
+        )
+        .body(Body::from(buffer.into_inner()))
+        .unwrap_or_default()
+}
+
+/// 递归地把`base`目录下的所有文件写入zip，zip内的条目路径相对于`root`
+fn add_dir_to_zip<W: std::io::Write + std::io::Seek>(
+    zip: &mut zip::ZipWriter<W>,
+    root: &std::path::Path,
+    base: &std::path::Path,
+    options: zip::write::SimpleFileOptions,
+) -> std::io::Result<()> {
+    for entry in std::fs::read_dir(base)?.filter_map(Result::ok) {
+        let path = entry.path();
+        if path.is_dir() {
+            add_dir_to_zip(zip, root, &path, options)?;
+        } else if let Ok(relative) = path.strip_prefix(root) {
+            zip.start_file(relative.to_slash_lossy(), options)?;
+            let data = std::fs::read(&path)?;
+            std::io::Write::write_all(zip, &data)?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,78 @@
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use tauri::{AppHandle, Manager};
+
+/// 对某个tag(通常是画师名等)的订阅，调度器定期用`tag`作关键词搜索`TimeNewest`结果，
+/// 与`last_seen_comic_ids`比对发现新作；见[`crate::commands::check_tag_subscriptions`]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct TagSubscription {
+    pub tag: String,
+    /// 上一次检查时搜索结果中出现过的漫画id，用于判断本次搜索结果里哪些是新出现的
+    #[serde(default)]
+    pub last_seen_comic_ids: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TagSubscriptionStore {
+    subscriptions: Vec<TagSubscription>,
+}
+
+impl TagSubscriptionStore {
+    pub fn new(app: &AppHandle) -> anyhow::Result<Self> {
+        let path = Self::path(app)?;
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let string = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&string).unwrap_or_default())
+    }
+
+    pub fn subscriptions(&self) -> Vec<TagSubscription> {
+        self.subscriptions.clone()
+    }
+
+    pub fn add(&mut self, tag: String) {
+        if self.subscriptions.iter().any(|s| s.tag == tag) {
+            return;
+        }
+        self.subscriptions.push(TagSubscription {
+            tag,
+            last_seen_comic_ids: vec![],
+        });
+    }
+
+    pub fn remove(&mut self, tag: &str) {
+        self.subscriptions.retain(|s| s.tag != tag);
+    }
+
+    /// 用本次搜索得到的`comic_ids`更新`tag`的`last_seen_comic_ids`，返回其中此前未出现过的id
+    pub fn mark_seen(&mut self, tag: &str, comic_ids: &[String]) -> Vec<String> {
+        let Some(subscription) = self.subscriptions.iter_mut().find(|s| s.tag == tag) else {
+            return vec![];
+        };
+        let new_ids: Vec<String> = comic_ids
+            .iter()
+            .filter(|id| !subscription.last_seen_comic_ids.contains(id))
+            .cloned()
+            .collect();
+        subscription.last_seen_comic_ids = comic_ids.to_vec();
+        new_ids
+    }
+
+    pub fn save(&self, app: &AppHandle) -> anyhow::Result<()> {
+        let path = Self::path(app)?;
+        let string = serde_json::to_string_pretty(self)?;
+        std::fs::write(path, string)?;
+        Ok(())
+    }
+
+    fn path(app: &AppHandle) -> anyhow::Result<std::path::PathBuf> {
+        let app_data_dir = app
+            .path()
+            .app_data_dir()
+            .context("failed to get app data dir")?;
+        Ok(app_data_dir.join("tag_subscriptions.json"))
+    }
+}
@@ -0,0 +1,47 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use rayon::prelude::*;
+
+/// 遍历`ep_dir`下所有已下载的图片，为每张图片生成/复用一份长边不超过`max_edge`像素的缩略图，
+/// 缓存到`cache_dir`中，返回按文件名排序后的缩略图本地路径列表，供前端阅读器懒加载原图前先展示缩略图
+///
+/// 已存在同名缓存文件时直接复用，不重复生成；生成使用rayon并行，避免章节图片较多时卡顿
+pub fn generate_chapter_thumbnails(
+    ep_dir: &Path,
+    cache_dir: &Path,
+    max_edge: u32,
+) -> anyhow::Result<Vec<PathBuf>> {
+    std::fs::create_dir_all(cache_dir)
+        .with_context(|| format!("创建缩略图缓存目录`{cache_dir:?}`失败"))?;
+
+    let mut image_paths: Vec<PathBuf> = std::fs::read_dir(ep_dir)
+        .with_context(|| format!("读取章节目录`{ep_dir:?}`失败"))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.is_file())
+        .collect();
+    image_paths.sort();
+
+    let thumbnail_paths: Vec<PathBuf> = image_paths
+        .iter()
+        .map(|src| {
+            let stem = src.file_stem().unwrap_or_default().to_string_lossy();
+            cache_dir.join(format!("{stem}_{max_edge}.jpg"))
+        })
+        .collect();
+
+    thumbnail_paths
+        .par_iter()
+        .zip(image_paths.par_iter())
+        .filter(|(dst, _)| !dst.exists())
+        .try_for_each(|(dst, src)| -> anyhow::Result<()> {
+            let img = image::open(src).with_context(|| format!("读取图片`{src:?}`失败"))?;
+            img.thumbnail(max_edge, max_edge)
+                .to_rgb8()
+                .save_with_format(dst, image::ImageFormat::Jpeg)
+                .with_context(|| format!("保存缩略图`{dst:?}`失败"))?;
+            Ok(())
+        })?;
+
+    Ok(thumbnail_paths)
+}
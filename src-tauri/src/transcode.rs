@@ -0,0 +1,171 @@
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::Mutex;
+
+use anyhow::Context;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+use crate::extensions::{AnyhowErrorToStringChain, IgnoreLockPoison};
+use crate::types::{BatchFailure, BatchSummary, ImageFormatReport, ImageFormatStat};
+
+/// 估算WebP转码节省比例时实际抽样编码的图片数量上限，避免大库时全量编码耗时过长
+const WEBP_SAVINGS_SAMPLE_LIMIT: usize = 30;
+
+/// 重新转码时的目标图片格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub enum TranscodeFormat {
+    Jpeg,
+    Png,
+    WebP,
+}
+
+/// 遍历`comic_dir`下所有章节目录，使用rayon并行把其中已下载的图片原地转码为`target_format`
+///
+/// `cancel_flag`被置位后，尚未开始转码的图片会被跳过并计入`BatchSummary::skipped`，
+/// 已分发给线程池的图片会正常转码完成。`on_progress`在每张图片处理完成后被调用一次，
+/// 参数为`(已处理数量, 总数量)`，供调用方上报进度事件
+pub fn transcode_comic(
+    comic_dir: &Path,
+    target_format: TranscodeFormat,
+    cancel_flag: &AtomicBool,
+    on_progress: impl Fn(u32, u32) + Sync,
+) -> anyhow::Result<BatchSummary> {
+    let mut image_paths: Vec<PathBuf> = vec![];
+    let ep_dirs = std::fs::read_dir(comic_dir)
+        .with_context(|| format!("读取漫画目录`{comic_dir:?}`失败"))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.is_dir());
+    for ep_dir in ep_dirs {
+        let images = std::fs::read_dir(&ep_dir)
+            .with_context(|| format!("读取章节目录`{ep_dir:?}`失败"))?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| path.is_file());
+        image_paths.extend(images);
+    }
+
+    let total = image_paths.len() as u32;
+    let completed = AtomicU32::new(0);
+    let summary = Mutex::new(BatchSummary::default());
+
+    image_paths.par_iter().for_each(|path| {
+        if cancel_flag.load(Ordering::Relaxed) {
+            summary.lock_or_panic().skipped += 1;
+        } else {
+            match transcode_image(path, target_format) {
+                Ok(()) => summary.lock_or_panic().succeeded += 1,
+                Err(err) => summary.lock_or_panic().failures.push(BatchFailure {
+                    item: path.to_string_lossy().to_string(),
+                    reason: err.to_string_chain(),
+                }),
+            }
+        }
+        let current = completed.fetch_add(1, Ordering::Relaxed) + 1;
+        on_progress(current, total);
+    });
+
+    Ok(summary.lock_or_panic().clone())
+}
+
+/// 把单张图片原地转码为`target_format`，转码后删除扩展名不同的旧文件
+fn transcode_image(path: &Path, target_format: TranscodeFormat) -> anyhow::Result<()> {
+    let (ext, image_format) = match target_format {
+        TranscodeFormat::Jpeg => ("jpg", image::ImageFormat::Jpeg),
+        TranscodeFormat::Png => ("png", image::ImageFormat::Png),
+        TranscodeFormat::WebP => ("webp", image::ImageFormat::WebP),
+    };
+    let new_path = path.with_extension(ext);
+    if new_path == *path {
+        return Ok(());
+    }
+
+    let img = image::open(path).with_context(|| format!("读取图片`{path:?}`失败"))?;
+    img.save_with_format(&new_path, image_format)
+        .with_context(|| format!("保存`{new_path:?}`失败"))?;
+    std::fs::remove_file(path).with_context(|| format!("删除旧文件`{path:?}`失败"))?;
+
+    Ok(())
+}
+
+/// 递归统计`dir`下各格式图片的数量与体积，并对非WebP图片抽样实际转码为WebP以估算节省空间，
+/// 作为`transcode_comic`的决策依据
+pub fn analyze_image_formats(dir: &Path) -> anyhow::Result<ImageFormatReport> {
+    let mut stats: Vec<ImageFormatStat> = vec![];
+    let mut non_webp_samples: Vec<PathBuf> = vec![];
+    collect_image_format_stats(dir, &mut stats, &mut non_webp_samples)?;
+
+    Ok(ImageFormatReport {
+        stats,
+        estimated_webp_savings_percent: estimate_webp_savings(&non_webp_samples),
+        avif_unsupported_reason: "当前未引入AVIF编码依赖，暂不提供AVIF空间预估".to_string(),
+    })
+}
+
+fn collect_image_format_stats(
+    dir: &Path,
+    stats: &mut Vec<ImageFormatStat>,
+    non_webp_samples: &mut Vec<PathBuf>,
+) -> anyhow::Result<()> {
+    for entry in std::fs::read_dir(dir).with_context(|| format!("读取目录`{dir:?}`失败"))? {
+        let path = entry
+            .with_context(|| format!("读取目录`{dir:?}`的条目失败"))?
+            .path();
+        if path.is_dir() {
+            collect_image_format_stats(&path, stats, non_webp_samples)?;
+            continue;
+        }
+        let Some(ext) = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(str::to_lowercase)
+        else {
+            continue;
+        };
+        if !matches!(ext.as_str(), "jpg" | "jpeg" | "png" | "webp") {
+            continue;
+        }
+        let size = std::fs::metadata(&path)
+            .with_context(|| format!("读取`{path:?}`的元数据失败"))?
+            .len();
+        match stats.iter_mut().find(|stat| stat.extension == ext) {
+            Some(stat) => {
+                stat.count += 1;
+                stat.total_bytes += size;
+            }
+            None => stats.push(ImageFormatStat {
+                extension: ext.clone(),
+                count: 1,
+                total_bytes: size,
+            }),
+        }
+        if ext != "webp" && non_webp_samples.len() < WEBP_SAVINGS_SAMPLE_LIMIT {
+            non_webp_samples.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// 对`samples`实际编码为WebP并比较体积，返回平均节省比例(0~100)；`samples`为空或编码均失败时返回`None`
+fn estimate_webp_savings(samples: &[PathBuf]) -> Option<f64> {
+    let mut original_total = 0u64;
+    let mut webp_total = 0u64;
+    for path in samples {
+        let Ok(original_size) = std::fs::metadata(path).map(|m| m.len()) else {
+            continue;
+        };
+        let Ok(img) = image::open(path) else {
+            continue;
+        };
+        let mut buf = std::io::Cursor::new(Vec::new());
+        if img.write_to(&mut buf, image::ImageFormat::WebP).is_err() {
+            continue;
+        }
+        original_total += original_size;
+        webp_total += buf.into_inner().len() as u64;
+    }
+    if original_total == 0 {
+        return None;
+    }
+    Some((1.0 - webp_total as f64 / original_total as f64) * 100.0)
+}
@@ -5,6 +5,7 @@ use crate::utils::filename_filter;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use specta::Type;
+use std::path::PathBuf;
 use std::sync::RwLock;
 use tauri::{AppHandle, Manager};
 
@@ -59,16 +60,39 @@ pub struct Comic {
     pub views_count: i64,
     pub is_liked: bool,
     pub comments_count: i64,
+    /// 按`Config.default_chapter_selection`计算出的推荐默认勾选章节id列表，
+    /// 供前端在下载面板打开时预先勾选，见[`Comic::recommended_checked_ep_ids`]
+    pub recommended_checked_ep_ids: Vec<String>,
+    /// `episodes`是否是在官方`eps`接口异常返回空列表时，退而用探测图片接口的方式尽力恢复出来的；
+    /// 为`true`时`episodes`中的标题均为占位的"第N话"，供前端提示用户章节信息可能不准确
+    pub episodes_recovered: bool,
 }
+/// 从章节标题中解析出第一段连续数字(如"第12话"->`Some(12)`)，解析失败返回`None`，
+/// 用作[`Comic::normalize_episode_order`]排序时的决胜字段
+fn parse_chapter_number_from_title(title: &str) -> Option<i64> {
+    let digits: String = title
+        .chars()
+        .skip_while(|c| !c.is_ascii_digit())
+        .take_while(char::is_ascii_digit)
+        .collect();
+    digits.parse().ok()
+}
+
 impl Comic {
-    pub fn from(app: &AppHandle, comic: ComicRespData, episodes: Vec<EpisodeRespData>) -> Self {
-        let comic_title = filename_filter(&comic.title);
-        let author = filename_filter(&comic.author);
+    pub fn from(
+        app: &AppHandle,
+        comic: ComicRespData,
+        episodes: Vec<EpisodeRespData>,
+        episodes_recovered: bool,
+    ) -> Self {
+        let comic_title = filename_filter(app, &comic.title);
+        let author = filename_filter(app, &comic.author);
 
-        let episodes: Vec<Episode> = episodes
+        let episodes: Vec<Episode> = Self::normalize_episode_order(episodes)
             .into_iter()
-            .map(|ep| {
-                let ep_title = filename_filter(&ep.title);
+            .enumerate()
+            .map(|(index, ep)| {
+                let ep_title = filename_filter(app, &ep.title);
                 let is_downloaded = Self::get_is_downloaded(app, &comic_title, &ep_title, &author);
                 Episode {
                     ep_id: ep.id,
@@ -77,11 +101,16 @@ impl Comic {
                     comic_title: comic_title.clone(),
                     author: author.clone(),
                     is_downloaded,
-                    order: ep.order,
+                    order: index as i64 + 1,
+                    raw_order: ep.order,
+                    is_locked: ep.is_locked,
+                    target_dir: None,
                 }
             })
             .collect();
 
+        let recommended_checked_ep_ids = Self::recommended_checked_ep_ids(app, &episodes);
+
         let thumb = Image {
             original_name: comic.thumb.original_name,
             path: comic.thumb.path,
@@ -128,25 +157,59 @@ impl Comic {
             views_count: comic.views_count,
             is_liked: comic.is_liked,
             comments_count: comic.comments_count,
+            recommended_checked_ep_ids,
+            episodes_recovered,
         }
     }
 
-    fn get_is_downloaded(app: &AppHandle, comic_title: &str, ep_title: &str, author: &str) -> bool {
-        let download_with_author = app
+    /// 按官方返回的`order`对章节稳定排序，以消除个别漫画`order`重复/缺号导致的下载目录序号混乱：
+    /// 先按`order`排序，`order`相同时依次以`updated_at`、标题中解析出的数字作为决胜；
+    /// 仍然相同则保留接口原始返回顺序(`sort_by`稳定排序的性质)。排序结果由调用方重新从1开始编号
+    fn normalize_episode_order(mut episodes: Vec<EpisodeRespData>) -> Vec<EpisodeRespData> {
+        episodes.sort_by(|a, b| {
+            a.order
+                .cmp(&b.order)
+                .then(a.updated_at.cmp(&b.updated_at))
+                .then(parse_chapter_number_from_title(&a.title).cmp(&parse_chapter_number_from_title(&b.title)))
+        });
+        episodes
+    }
+
+    /// 按`Config.default_chapter_selection`从`episodes`中算出推荐默认勾选的章节id列表
+    fn recommended_checked_ep_ids(app: &AppHandle, episodes: &[Episode]) -> Vec<String> {
+        let default_chapter_selection = app
             .state::<RwLock<Config>>()
             .read_or_panic()
-            .download_with_author;
-        let comic_title = if download_with_author {
-            &format!("[{author}] {comic_title}")
-        } else {
-            comic_title
-        };
-        app.state::<RwLock<Config>>()
-            .read_or_panic()
-            .download_dir
-            .join(comic_title)
-            .join(ep_title)
-            .exists()
+            .default_chapter_selection;
+        match default_chapter_selection {
+            DefaultChapterSelection::All => episodes.iter().map(|ep| ep.ep_id.clone()).collect(),
+            DefaultChapterSelection::Undownloaded => episodes
+                .iter()
+                .filter(|ep| !ep.is_downloaded)
+                .map(|ep| ep.ep_id.clone())
+                .collect(),
+            DefaultChapterSelection::LatestOnly => episodes
+                .iter()
+                .max_by_key(|ep| ep.order)
+                .map(|ep| vec![ep.ep_id.clone()])
+                .unwrap_or_default(),
+        }
+    }
+
+    /// 判断章节是否已下载：会遍历所有库分区(`all_library_dirs`)，且同时尝试
+    /// 带作者前缀与不带作者前缀两种目录命名，只要在任意一个版本中存在即视为已下载
+    fn get_is_downloaded(app: &AppHandle, comic_title: &str, ep_title: &str, author: &str) -> bool {
+        let config = app.state::<RwLock<Config>>();
+        let config = config.read_or_panic();
+        let comic_title_variants = [
+            comic_title.to_string(),
+            format!("[{author}] {comic_title}"),
+        ];
+        config.all_library_dirs().iter().any(|library_dir| {
+            comic_title_variants
+                .iter()
+                .any(|title| library_dir.dir.join(title).join(ep_title).exists())
+        })
     }
 }
 
@@ -160,7 +223,108 @@ pub struct Episode {
     pub comic_title: String,
     pub author: String,
     pub is_downloaded: bool,
+    /// 规范化后的顺序号，从1开始连续递增；当官方接口返回的`raw_order`有重复或缺号时，
+    /// 由[`Comic::from`]按`raw_order`/`updated_at`/标题中的数字稳定排序后重新编号而来，
+    /// 下载目录排序、`EpisodeSelection`筛选均应使用该字段而非`raw_order`
     pub order: i64,
+    /// 官方接口原始返回的顺序号，可能重复或跳号，仅作展示/排查问题用，见[`Self::order`]
+    #[serde(default)]
+    pub raw_order: i64,
+    /// 锁定/付费章节，当前账号可能无法访问，批量下载时会自动跳过，见[`crate::commands::download_episodes`]
+    #[serde(default)]
+    pub is_locked: bool,
+    /// 任务级别的保存目录，覆盖全局`download_dir`，用于把个别漫画下载到特定位置(如不同磁盘)；
+    /// 见[`crate::commands::download_comic`]
+    #[serde(default)]
+    pub target_dir: Option<PathBuf>,
+}
+impl Episode {
+    /// 该章节的下载目录：存在`target_dir`时优先使用，否则落回全局`download_dir`
+    pub fn dir_path(&self, app: &AppHandle) -> PathBuf {
+        let download_with_author = app
+            .state::<RwLock<Config>>()
+            .read_or_panic()
+            .download_with_author;
+        let comic_title = if download_with_author {
+            &format!("[{}] {}", self.author, self.comic_title)
+        } else {
+            &self.comic_title
+        };
+        let base_dir = self.target_dir.clone().unwrap_or_else(|| {
+            app.state::<RwLock<Config>>()
+                .read_or_panic()
+                .download_dir
+                .clone()
+        });
+        base_dir.join(comic_title).join(&self.ep_title)
+    }
+}
+
+/// [`EpisodeSelection`]的筛选方式，`count`/`order_start`/`order_end`按所选方式解释，其余字段忽略
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub enum EpisodeSelectionMode {
+    /// 下载全部章节
+    #[default]
+    All,
+    /// 只下载最前面的`count`话(按`order`升序取前`count`个)
+    FirstN,
+    /// 只下载最新的`count`话(按`order`降序取前`count`个)
+    LastN,
+    /// 只下载`order`落在`[order_start, order_end]`闭区间内的章节
+    OrderRange,
+}
+
+/// 点击漫画详情页的"下载"时，默认帮用户勾选哪些章节，见[`Comic::recommended_checked_ep_ids`]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub enum DefaultChapterSelection {
+    /// 默认全选
+    #[default]
+    All,
+    /// 只默认勾选尚未下载的章节
+    Undownloaded,
+    /// 只默认勾选`order`最大的一话
+    LatestOnly,
+}
+
+/// 批量下载一本漫画时选择哪些章节，用于"只想先囤每本的最新几话"等场景，减少一次性任务量；
+/// 见[`crate::commands::download_comic`]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct EpisodeSelection {
+    pub mode: EpisodeSelectionMode,
+    pub count: Option<u32>,
+    pub order_start: Option<i64>,
+    pub order_end: Option<i64>,
+}
+
+impl EpisodeSelection {
+    /// 按当前策略从`episodes`中筛选出需要下载的章节：`All`/`OrderRange`不改变剩余章节的相对顺序，
+    /// `FirstN`/`LastN`先按`order`排序再截取，`LastN`的结果按`order`降序排列
+    pub fn apply(self, mut episodes: Vec<Episode>) -> Vec<Episode> {
+        match self.mode {
+            EpisodeSelectionMode::All => episodes,
+            EpisodeSelectionMode::FirstN => {
+                let count = self.count.unwrap_or(0) as usize;
+                episodes.sort_by_key(|ep| ep.order);
+                episodes.truncate(count);
+                episodes
+            }
+            EpisodeSelectionMode::LastN => {
+                let count = self.count.unwrap_or(0) as usize;
+                episodes.sort_by_key(|ep| std::cmp::Reverse(ep.order));
+                episodes.truncate(count);
+                episodes
+            }
+            EpisodeSelectionMode::OrderRange => {
+                let start = self.order_start.unwrap_or(i64::MIN);
+                let end = self.order_end.unwrap_or(i64::MAX);
+                episodes
+                    .into_iter()
+                    .filter(|ep| ep.order >= start && ep.order <= end)
+                    .collect()
+            }
+        }
+    }
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
@@ -191,3 +355,369 @@ pub struct Image {
     pub path: String,
     pub file_server: String,
 }
+
+/// 批量下载操作的dry-run预览结果：只汇报将会下载的内容，不实际创建下载任务
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct DownloadPreview {
+    pub comic_title: String,
+    pub episode_count: usize,
+    pub episode_titles: Vec<String>,
+}
+
+/// 一个带标签的下载根目录，用于支持多下载根目录（库分区）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryDir {
+    pub label: String,
+    pub dir: PathBuf,
+}
+
+/// 按图片格式统计的库内数量与体积，见[`crate::commands::analyze_image_formats`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ImageFormatStat {
+    pub extension: String,
+    pub count: u64,
+    pub total_bytes: u64,
+}
+
+/// 库内图片格式统计与转码空间预估，作为[`crate::commands::transcode_downloaded`]的决策依据
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ImageFormatReport {
+    pub stats: Vec<ImageFormatStat>,
+    /// 对非WebP图片抽样实际编码为WebP后估算出的体积节省比例(0~100)，抽样为空时为`None`
+    pub estimated_webp_savings_percent: Option<f64>,
+    /// 当前未引入AVIF编码依赖，暂不提供AVIF空间预估
+    pub avif_unsupported_reason: String,
+}
+
+/// 按归一化标题聚合后的一组搜索结果，`comics.len() > 1`表示疑似同一作品的多个重复上传版本；
+/// 见[`crate::commands::search_comic_grouped`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchResultGroup {
+    pub normalized_title: String,
+    pub comics: Vec<crate::responses::ComicInSearchRespData>,
+}
+
+/// `compare_comics`中单个漫画的对比信息；见[`crate::commands::compare_comics`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ComicComparisonEntry {
+    pub comic_id: String,
+    pub title: String,
+    pub pages_count: i64,
+    pub eps_count: i64,
+    pub updated_at: DateTime<Utc>,
+    pub tags: Vec<String>,
+    /// 该版本独有、其余被比较版本都不包含的标签
+    pub unique_tags: Vec<String>,
+}
+
+/// `compare_comics`的返回结果
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ComicComparisonReport {
+    pub entries: Vec<ComicComparisonEntry>,
+    /// 获取详情失败的漫画id，不阻塞其余漫画的对比结果
+    pub failed_comic_ids: Vec<String>,
+}
+
+/// `health_check`中单项检查的结果
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct HealthCheckItem {
+    pub name: String,
+    pub passed: bool,
+    pub message: String,
+    pub suggestion: Option<String>,
+}
+
+/// 官方标记为`allow_download=false`的漫画，创建下载任务时的处理策略
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub enum DisallowedDownloadPolicy {
+    /// 仍然尝试下载，但在结果摘要中给出警告
+    #[default]
+    Warn,
+    /// 直接跳过，不创建下载任务
+    Skip,
+    /// 不做任何提示，当作普通漫画处理
+    Force,
+}
+
+/// 界面与错误文案使用的语言，见[`crate::i18n::t`]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub enum Language {
+    #[default]
+    Zh,
+    En,
+}
+
+/// [`PicaClient`](crate::pica_client::PicaClient)请求API时优先使用的域名通道，配合
+/// `Config.pica_backup_host`在主域名被风控时提高可用性
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub enum PicaChannel {
+    /// 优先使用主域名，请求失败且已配置`pica_backup_host`时自动改用备用域名重试一次
+    #[default]
+    Auto,
+    /// 只使用主域名，即使已配置备用域名也不自动切换
+    Primary,
+    /// 优先使用`pica_backup_host`，未配置时退回主域名
+    Backup,
+}
+
+/// 请求头`app-channel`的取值，对应哔咔官方客户端可切换的分流线路，同一域名下不同线路的
+/// 连通性可能差异很大；见[`crate::pica_client::PicaClient::test_channels`]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub enum ApiChannel {
+    One,
+    #[default]
+    Two,
+    Three,
+}
+
+impl ApiChannel {
+    /// 该分流线路对应的`app-channel`请求头取值
+    pub fn header_value(self) -> &'static str {
+        match self {
+            ApiChannel::One => "1",
+            ApiChannel::Two => "2",
+            ApiChannel::Three => "3",
+        }
+    }
+
+    /// 所有可选的分流线路，用于[`crate::pica_client::PicaClient::test_channels`]逐一测速
+    pub fn all() -> [ApiChannel; 3] {
+        [ApiChannel::One, ApiChannel::Two, ApiChannel::Three]
+    }
+}
+
+/// [`crate::pica_client::PicaClient::test_channels`]中单条分流线路的测速结果
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiChannelLatency {
+    pub channel: ApiChannel,
+    /// 请求耗时(毫秒)，请求失败时为`None`
+    pub latency_ms: Option<u64>,
+    /// 请求失败时的错误信息
+    pub error: Option<String>,
+}
+
+/// 请求头`image-quality`的取值，控制哔咔返回的图片压缩程度，流量有限时可选择压缩过的图片
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub enum ImageQuality {
+    #[default]
+    Original,
+    High,
+    Medium,
+    Low,
+}
+
+impl ImageQuality {
+    /// 该画质对应的`image-quality`请求头取值
+    pub fn header_value(self) -> &'static str {
+        match self {
+            ImageQuality::Original => "original",
+            ImageQuality::High => "high",
+            ImageQuality::Medium => "medium",
+            ImageQuality::Low => "low",
+        }
+    }
+}
+
+/// 全部下载任务完成且队列为空时自动执行的电源操作，默认关闭，
+/// 见[`crate::download_manager::DownloadManager::maybe_trigger_auto_power_action`]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub enum AutoPowerAction {
+    #[default]
+    Off,
+    Sleep,
+    Shutdown,
+}
+
+/// 网络请求使用的代理策略，见[`crate::config::Config::apply_proxy`]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub enum ProxyMode {
+    /// 不做任何代理相关设置，沿用reqwest对系统代理环境变量/设置的默认识别
+    #[default]
+    System,
+    /// 使用`Config.proxy_scheme`/`proxy_host`/`proxy_port`拼出的自定义代理
+    Custom,
+    /// 显式禁用代理，即使系统配置了代理也不使用
+    Disabled,
+}
+
+/// `ProxyMode::Custom`下自定义代理使用的协议
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub enum ProxyScheme {
+    #[default]
+    Http,
+    Socks5,
+}
+
+/// 批量操作中单个失败项的原因
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchFailure {
+    pub item: String,
+    pub reason: String,
+}
+
+/// 批量command的结构化结果摘要，取代过去只返回`()`只能翻日志排查的方式
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchSummary {
+    pub succeeded: u32,
+    pub skipped: u32,
+    pub failures: Vec<BatchFailure>,
+    /// 非致命的提示信息，例如漫画被官方标记为禁止下载但仍尝试强制下载
+    pub warning: Option<String>,
+}
+
+/// 按拼音首字母分组后的一组漫画标题，供前端实现A-Z快速索引
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct PinyinGroup {
+    pub letter: String,
+    pub comic_titles: Vec<String>,
+}
+
+/// 下载速度历史中的单个采样点，见[`crate::download_manager::DownloadManager::get_speed_history`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct SpeedSample {
+    /// 采样时刻的Unix时间戳(秒)
+    pub timestamp: i64,
+    pub byte_per_sec: u64,
+}
+
+/// 单本漫画当前的聚合下载进度，供前端按漫画(而非章节)展示整体进度
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ComicDownloadProgress {
+    pub comic_id: String,
+    pub comic_title: String,
+    pub total_episode_count: u32,
+    pub completed_episode_count: u32,
+    pub percentage: f64,
+}
+
+/// 单本漫画在`download_dir`下的磁盘占用情况
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ComicDiskUsage {
+    pub comic_title: String,
+    pub total_bytes: u64,
+    pub image_count: u64,
+    pub avg_image_bytes: u64,
+}
+
+/// [`crate::commands::get_app_info`]的返回值：版本、平台、数据目录与库统计概览，
+/// 用户提issue时可以把这些信息一键复制进反馈里，省去来回追问环境细节
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct AppInfo {
+    pub version: String,
+    pub os: String,
+    pub arch: String,
+    pub app_data_dir: PathBuf,
+    pub download_dir: PathBuf,
+    pub comic_count: u32,
+    pub total_bytes: u64,
+    /// 事件发送(`.emit()`)失败的累计次数，用于排查"前端收不到进度/日志"一类问题
+    pub emit_failure_count: u64,
+}
+
+/// 单本漫画已下载的章节清单，用于跨设备库同步
+///
+/// 库中的漫画/章节目录都按标题命名而非id(见[`crate::commands::create_comic_title_to_dir_map`])，
+/// 本地也不会额外持久化漫画/章节的id，因此清单中用标题而非id标识漫画与章节
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ComicManifestEntry {
+    pub comic_title: String,
+    pub ep_titles: Vec<String>,
+}
+
+/// 一台设备上已下载漫画的完整清单，可导出为文件后在另一台设备上导入对比
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryManifest {
+    pub comics: Vec<ComicManifestEntry>,
+}
+
+/// 用导入的[`LibraryManifest`]与本机库比对后，本机缺少的章节
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct MissingEpisodes {
+    pub comic_title: String,
+    pub ep_titles: Vec<String>,
+}
+
+/// 某个分类/标签下，本地库相对线上的覆盖率，见[`crate::commands::coverage_report`]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct CoverageReport {
+    pub term: String,
+    /// 该分类/标签下线上的漫画总数
+    pub online_total: i64,
+    /// 本地已下载、标签中包含该分类/标签的漫画数量
+    pub local_count: i64,
+    /// `local_count / online_total`，`online_total`为0时为0
+    pub coverage_percent: f64,
+}
+
+/// `sync_library_to_favorites`的同步方向
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub enum FavoriteSyncDirection {
+    /// 把本地已下载但未收藏的漫画批量收藏(调用收藏API)
+    ToFavorites,
+    /// 反向：把本地已下载、但当前收藏夹中已不存在的漫画在本地标记(只打本地标签，不调用API)
+    MarkRemovedLocally,
+}
+
+/// `sync_library_to_favorites`的结构化结果
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct FavoriteSyncReport {
+    /// `ToFavorites`下已(或将要，取决于`dry_run`)收藏成功的标题；`MarkRemovedLocally`下已(或将要)标记的标题
+    pub affected: Vec<String>,
+    /// 本地已下载、当前未收藏，但无法通过标题精确匹配到唯一一本线上漫画的标题，需要用户手动处理
+    pub unresolved: Vec<String>,
+    pub failures: Vec<BatchFailure>,
+}
+
+/// [`crate::download_manager::DownloadManager::circuit_breaker_status`]的返回结构
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct CircuitBreakerStatus {
+    /// 是否处于熔断状态，熔断期间所有下载任务都会暂停
+    pub broken: bool,
+    /// 触发熔断的原因，未熔断时为`None`
+    pub reason: Option<String>,
+}
+
+/// [`crate::commands::get_download_quota_status`]的返回结构
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct DownloadQuotaStatus {
+    pub downloaded_image_count: u32,
+    /// 每日最大下载图片数配额，`None`表示不限制
+    pub image_quota: Option<u32>,
+    pub downloaded_episode_count: u32,
+    /// 每日最大下载章节数配额，`None`表示不限制
+    pub episode_quota: Option<u32>,
+}
+
+/// [`crate::commands::debug_download_image`]的返回结构，用于排查单个图片URL的下载问题
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct DebugDownloadImageResult {
+    pub status_code: u16,
+    pub byte_count: u64,
+    /// 根据图片文件头猜测出的格式，例如`png`/`jpeg`；无法识别时为`None`
+    pub guessed_format: Option<String>,
+    pub saved_path: String,
+}
@@ -1,5 +1,186 @@
-pub fn filename_filter(s: &str) -> String {
-    s.chars()
+/// 查询`path`所在磁盘/分区的剩余可用空间(字节)；`path`本身不需要存在，会沿祖先目录向上查找第一个存在的目录
+///
+/// 标准库未提供跨平台的磁盘空间查询API，这里按平台分别shell出系统自带工具解析输出，
+/// 避免为此引入新的第三方依赖；任何一步解析失败都返回`None`，调用方应将其视为"无法判断"而非"空间不足"
+pub fn available_space(path: &std::path::Path) -> Option<u64> {
+    let existing = path.ancestors().find(|p| p.exists())?;
+
+    #[cfg(target_os = "windows")]
+    {
+        let output = std::process::Command::new("cmd")
+            .args(["/C", "dir", "/-C"])
+            .arg(existing)
+            .output()
+            .ok()?;
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let last_line = stdout.lines().filter(|l| !l.trim().is_empty()).next_back()?;
+        // 最后一行形如`2 Dir(s)  123456 bytes free`，取其中最后一个纯数字字段，避免把目录数一并拼进去
+        let available = last_line
+            .split_whitespace()
+            .rev()
+            .find(|token| token.chars().all(|c| c.is_ascii_digit()))?;
+        available.parse().ok()
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let output = std::process::Command::new("df")
+            .args(["-Pk"])
+            .arg(existing)
+            .output()
+            .ok()?;
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let fields: Vec<&str> = stdout.lines().nth(1)?.split_whitespace().collect();
+        let available_kb: u64 = fields.get(3)?.parse().ok()?;
+        Some(available_kb * 1024)
+    }
+}
+
+/// 递归计算目录下所有文件的总大小(字节)
+pub fn dir_size(path: &std::path::Path) -> std::io::Result<u64> {
+    let mut size = 0;
+    for entry in std::fs::read_dir(path)? {
+        let entry = entry?;
+        let metadata = entry.metadata()?;
+        if metadata.is_dir() {
+            size += dir_size(&entry.path())?;
+        } else {
+            size += metadata.len();
+        }
+    }
+    Ok(size)
+}
+
+/// 读取图片的EXIF Orientation标签并应用旋转/翻转，统一输出正向图片
+///
+/// 没有EXIF信息、或Orientation本就是正向(1)时，不做任何改动
+pub fn correct_exif_orientation(path: &std::path::Path) -> anyhow::Result<()> {
+    let file = std::fs::File::open(path)?;
+    let mut buf_reader = std::io::BufReader::new(file);
+    let exif = match exif::Reader::new().read_from_container(&mut buf_reader) {
+        Ok(exif) => exif,
+        Err(_) => return Ok(()), // 没有EXIF信息，不需要矫正
+    };
+
+    let Some(field) = exif.get_field(exif::Tag::Orientation, exif::In::PRIMARY) else {
+        return Ok(());
+    };
+    let orientation = field.value.get_uint(0).unwrap_or(1);
+    if orientation == 1 {
+        return Ok(());
+    }
+
+    let img = image::open(path)?;
+    let img = match orientation {
+        2 => img.fliph(),
+        3 => img.rotate180(),
+        4 => img.flipv(),
+        5 => img.rotate90().fliph(),
+        6 => img.rotate90(),
+        7 => img.rotate270().fliph(),
+        8 => img.rotate270(),
+        _ => img,
+    };
+    img.save(path)?;
+
+    Ok(())
+}
+
+/// 计算一个标题用于排序/分组的拼音首字母，ASCII字母直接大写，汉字转拼音取首字母，其余归入`#`
+fn pinyin_initial(title: &str) -> char {
+    use pinyin::ToPinyin;
+    let Some(first) = title.chars().next() else {
+        return '#';
+    };
+    if first.is_ascii_alphabetic() {
+        return first.to_ascii_uppercase();
+    }
+    first
+        .to_pinyin()
+        .and_then(|p| p.plain().chars().next())
+        .map(|c| c.to_ascii_uppercase())
+        .unwrap_or('#')
+}
+
+/// 把标题归一化以识别疑似重复上传：去除方括号/圆括号标注(通常是汉化组名)、空白字符，
+/// 并统一转为小写，仅用于比较，不用于展示
+fn normalize_title(title: &str) -> String {
+    let mut normalized = String::with_capacity(title.len());
+    let mut depth = 0;
+    for c in title.chars() {
+        match c {
+            '[' | '(' | '（' | '【' => depth += 1,
+            ']' | ')' | '）' | '】' => depth = depth.saturating_sub(1),
+            _ if depth == 0 && !c.is_whitespace() => {
+                normalized.extend(c.to_lowercase());
+            }
+            _ => {}
+        }
+    }
+    normalized
+}
+
+/// 按归一化后的标题对搜索结果分组，识别哔咔上同一作品的多个重复上传版本，
+/// 分组内按`pages_count`从多到少排序，方便用户优先选择页数最多的版本
+pub fn group_by_similar_title(
+    comics: Vec<crate::responses::ComicInSearchRespData>,
+) -> Vec<crate::types::SearchResultGroup> {
+    let mut groups: Vec<crate::types::SearchResultGroup> = vec![];
+    for comic in comics {
+        let normalized_title = normalize_title(&comic.title);
+        match groups
+            .iter_mut()
+            .find(|group| group.normalized_title == normalized_title)
+        {
+            Some(group) => group.comics.push(comic),
+            None => groups.push(crate::types::SearchResultGroup {
+                normalized_title,
+                comics: vec![comic],
+            }),
+        }
+    }
+    for group in &mut groups {
+        group
+            .comics
+            .sort_by(|a, b| b.pages_count.cmp(&a.pages_count));
+    }
+    groups
+}
+
+/// 按拼音首字母对漫画标题排序并分组，供前端实现A-Z快速索引
+pub fn group_by_pinyin(mut comic_titles: Vec<String>) -> Vec<crate::types::PinyinGroup> {
+    comic_titles.sort_by(|a, b| (pinyin_initial(a), a).cmp(&(pinyin_initial(b), b)));
+
+    let mut groups: Vec<crate::types::PinyinGroup> = vec![];
+    for comic_title in comic_titles {
+        let letter = pinyin_initial(&comic_title).to_string();
+        match groups.last_mut() {
+            Some(group) if group.letter == letter => group.comic_titles.push(comic_title),
+            _ => groups.push(crate::types::PinyinGroup {
+                letter,
+                comic_titles: vec![comic_title],
+            }),
+        }
+    }
+    groups
+}
+
+/// 把字符串中的文件系统非法字符替换为视觉上相近的合法字符，再按`Config::filename_filter_rules`
+/// 应用用户自定义的替换/emoji移除/全角转半角规则
+pub fn filename_filter(app: &tauri::AppHandle, s: &str) -> String {
+    use crate::config::Config;
+    use crate::extensions::IgnoreRwLockPoison;
+    use std::sync::RwLock;
+    use tauri::Manager;
+
+    let rules = app
+        .state::<RwLock<Config>>()
+        .read_or_panic()
+        .filename_filter_rules
+        .clone();
+
+    let builtin_filtered: String = s
+        .chars()
         .map(|c| match c {
             '\\' | '/' => ' ',
             ':' => '：',
@@ -12,7 +193,48 @@ pub fn filename_filter(s: &str) -> String {
             '.' => '·',
             _ => c,
         })
-        .collect::<String>()
-        .trim()
-        .to_string()
+        .collect();
+
+    let mut result = String::with_capacity(builtin_filtered.len());
+    'chars: for c in builtin_filtered.chars() {
+        for replacement in &rules.custom_replacements {
+            if replacement.from.chars().eq(std::iter::once(c)) {
+                result.push_str(&replacement.to);
+                continue 'chars;
+            }
+        }
+        if rules.remove_emoji && is_emoji(c) {
+            continue;
+        }
+        result.push(if rules.fullwidth_to_halfwidth {
+            to_halfwidth(c)
+        } else {
+            c
+        });
+    }
+
+    result.trim().to_string()
+}
+
+/// 粗略判断字符是否属于常见的emoji区块
+fn is_emoji(c: char) -> bool {
+    matches!(
+        u32::from(c),
+        0x1F300..=0x1FAFF
+            | 0x2600..=0x27BF
+            | 0x2190..=0x21FF
+            | 0x2B00..=0x2BFF
+            | 0x1F1E6..=0x1F1FF
+    )
+}
+
+/// 把全角字符转换为对应的半角字符，其余字符原样返回
+fn to_halfwidth(c: char) -> char {
+    match c {
+        '\u{3000}' => ' ',
+        '\u{FF01}'..='\u{FF5E}' => {
+            char::from_u32(u32::from(c) - 0xFEE0).unwrap_or(c)
+        }
+        _ => c,
+    }
 }
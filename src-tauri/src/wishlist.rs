@@ -0,0 +1,62 @@
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use tauri::{AppHandle, Manager};
+
+use crate::types::Image;
+
+/// “稍后下载”清单里的一项
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct WishlistItem {
+    pub comic_id: String,
+    pub title: String,
+    pub author: String,
+    pub thumb: Image,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Wishlist {
+    items: Vec<WishlistItem>,
+}
+
+impl Wishlist {
+    pub fn new(app: &AppHandle) -> anyhow::Result<Self> {
+        let wishlist_path = Self::path(app)?;
+        if !wishlist_path.exists() {
+            return Ok(Self::default());
+        }
+        let wishlist_string = std::fs::read_to_string(wishlist_path)?;
+        Ok(serde_json::from_str(&wishlist_string).unwrap_or_default())
+    }
+
+    pub fn items(&self) -> Vec<WishlistItem> {
+        self.items.clone()
+    }
+
+    pub fn add(&mut self, item: WishlistItem) {
+        if self.items.iter().any(|i| i.comic_id == item.comic_id) {
+            return;
+        }
+        self.items.push(item);
+    }
+
+    pub fn remove(&mut self, comic_id: &str) {
+        self.items.retain(|item| item.comic_id != comic_id);
+    }
+
+    pub fn save(&self, app: &AppHandle) -> anyhow::Result<()> {
+        let wishlist_path = Self::path(app)?;
+        let wishlist_string = serde_json::to_string_pretty(self)?;
+        std::fs::write(wishlist_path, wishlist_string)?;
+        Ok(())
+    }
+
+    fn path(app: &AppHandle) -> anyhow::Result<std::path::PathBuf> {
+        let app_data_dir = app
+            .path()
+            .app_data_dir()
+            .context("failed to get app data dir")?;
+        Ok(app_data_dir.join("wishlist.json"))
+    }
+}